@@ -0,0 +1,151 @@
+//! Shows how to drive a character mesh from [`CharacterAnimationState`] and [`AnimationCue`]s.
+//!
+//! The "rig" here is a simple body with a head, viewed from a follow camera. In a real game, you
+//! would play the clips of your rigged model's `AnimationPlayer` in the cue observer instead and
+//! feed the animation state into the weights of its animation graph.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_ahoy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::util::ExampleUtilPlugin;
+
+mod util;
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PhysicsPlugins::default(),
+            EnhancedInputPlugin,
+            AhoyPlugins::default(),
+            AhoyExtraPlugins::default(),
+            ExampleUtilPlugin,
+        ))
+        .add_input_context::<PlayerInput>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (animate_body, follow_player))
+        .add_observer(play_cue)
+        .run()
+}
+
+fn setup(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.9, 0.5, 0.2));
+    commands
+        .spawn((
+            CharacterController::default(),
+            Collider::cylinder(0.7, 1.8),
+            Transform::from_xyz(0.0, 20.0, 0.0),
+            PlayerInput,
+            actions!(PlayerInput[
+                (
+                    Action::<Movement>::new(),
+                    DeadZone::default(),
+                    Bindings::spawn((
+                        Cardinal::wasd_keys(),
+                        Axial::left_stick()
+                    ))
+                ),
+                (
+                    Action::<Jump>::new(),
+                    bindings![KeyCode::Space,  GamepadButton::South],
+                ),
+                (
+                    Action::<Crouch>::new(),
+                    bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
+                ),
+            ]),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Body::default(),
+                    Mesh3d(meshes.add(Capsule3d::new(0.35, 1.1))),
+                    MeshMaterial3d(material.clone()),
+                    Transform::default(),
+                ))
+                .with_child((
+                    Mesh3d(meshes.add(Sphere::new(0.25))),
+                    MeshMaterial3d(material),
+                    Transform::from_xyz(0.0, 0.85, 0.0),
+                ));
+        });
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 23.0, 6.0).looking_at(vec3(0.0, 20.0, 0.0), Vec3::Y),
+    ));
+
+    commands.spawn((
+        Transform::from_xyz(0.0, 1.0, 0.0).looking_at(vec3(1.0, -2.0, -2.0), Vec3::Y),
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        SceneRoot(assets.load("maps/playground.glb#Scene0")),
+        RigidBody::Static,
+        ColliderConstructorHierarchy::new(ColliderConstructor::ConvexHullFromMesh),
+    ));
+}
+
+/// The visual body of the character, squashed and stretched by the cues.
+#[derive(Component, Default)]
+struct Body {
+    squash: f32,
+}
+
+/// Starts the "animations" of a cue, like you would start a clip on an `AnimationPlayer`.
+fn play_cue(cue: On<AnimationCue>, children: Query<&Children>, mut bodies: Query<&mut Body>) {
+    let Ok(children) = children.get(cue.entity) else {
+        return;
+    };
+    let mut bodies = bodies.iter_many_mut(children);
+    while let Some(mut body) = bodies.fetch_next() {
+        body.squash = match cue.kind {
+            AnimationCueKind::JumpStart => -0.3,
+            AnimationCueKind::Land => 0.4,
+            AnimationCueKind::Step => 0.08,
+            AnimationCueKind::WaterEnter => 0.6,
+        };
+    }
+}
+
+/// Blends the body like an animation graph would, using the animation state as weights.
+fn animate_body(
+    characters: Query<(&CharacterAnimationState, &Children)>,
+    mut bodies: Query<(&mut Body, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (anim, children) in &characters {
+        let mut bodies = bodies.iter_many_mut(children);
+        while let Some((mut body, mut transform)) = bodies.fetch_next() {
+            body.squash *= (-10.0 * time.delta_secs()).exp();
+            let crouch = 1.0 - 0.35 * anim.crouch_fraction;
+            transform.scale = vec3(1.0 + body.squash * 0.5, (1.0 - body.squash) * crouch, 1.0);
+            transform.translation.y = -0.9 * (1.0 - transform.scale.y);
+            transform.rotation = Quat::from_rotation_z(-anim.lean * 0.3)
+                * Quat::from_rotation_x(-anim.strafe.y * 0.15);
+        }
+    }
+}
+
+fn follow_player(
+    player: Single<&Transform, With<PlayerInput>>,
+    mut camera: Single<&mut Transform, (With<Camera3d>, Without<PlayerInput>)>,
+) {
+    let target = player.translation;
+    camera.translation = target + vec3(0.0, 3.0, 6.0);
+    camera.look_at(target, Vec3::Y);
+}
+
+#[derive(Component, Default)]
+pub(crate) struct PlayerInput;
@@ -60,6 +60,10 @@ fn setup(
                     Action::<Crouch>::new(),
                     bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
                 ),
+                (
+                    Action::<Sprint>::new(),
+                    bindings![KeyCode::ShiftLeft, GamepadButton::LeftThumb],
+                ),
                 (
                     Action::<RotateCamera>::new(),
                     Bindings::spawn((
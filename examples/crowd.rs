@@ -0,0 +1,180 @@
+//! Stress-tests the controller with a crowd of hundreds of wandering NPCs.
+//!
+//! The NPCs are driven by writing their [`AccumulatedInput`] and [`CharacterLook`] directly,
+//! which is how you would hook up your own AI. All characters are moved in parallel, so this
+//! scales with the number of cores.
+
+use std::f32::consts::TAU;
+
+use avian3d::prelude::*;
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+use bevy_ahoy::{CharacterLook, input::AccumulatedInput, prelude::*};
+use bevy_enhanced_input::prelude::*;
+use bevy_time::Stopwatch;
+
+use crate::util::ExampleUtilPlugin;
+
+mod util;
+
+/// How many NPCs are spawned along each side of the grid.
+const CROWD_SIDE: u32 = 20;
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PhysicsPlugins::default(),
+            EnhancedInputPlugin,
+            AhoyPlugins::default(),
+            ExampleUtilPlugin,
+        ))
+        .add_input_context::<PlayerInput>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                capture_cursor.run_if(input_just_pressed(MouseButton::Left)),
+                release_cursor.run_if(input_just_pressed(KeyCode::Escape)),
+            ),
+        )
+        .add_systems(FixedUpdate, wander)
+        .run()
+}
+
+fn setup(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let player = commands
+        .spawn((
+            CharacterController::default(),
+            Collider::cylinder(0.7, 1.8),
+            Transform::from_xyz(0.0, 20.0, 0.0),
+            PlayerInput,
+            actions!(PlayerInput[
+                (
+                    Action::<Movement>::new(),
+                    DeadZone::default(),
+                    Bindings::spawn((
+                        Cardinal::wasd_keys(),
+                        Axial::left_stick()
+                    ))
+                ),
+                (
+                    Action::<Jump>::new(),
+                    bindings![KeyCode::Space,  GamepadButton::South],
+                ),
+                (
+                    Action::<Crouch>::new(),
+                    bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
+                ),
+                (
+                    Action::<RotateCamera>::new(),
+                    Bindings::spawn((
+                        Spawn((Binding::mouse_motion(), Scale::splat(0.07))),
+                        Axial::right_stick().with((Scale::splat(4.0), DeadZone::default())),
+                    ))
+                ),
+            ]),
+        ))
+        .id();
+
+    commands.spawn((
+        Camera3d::default(),
+        CharacterControllerCameraOf::new(player),
+    ));
+
+    let mesh = meshes.add(Cylinder::new(0.35, 1.8));
+    let material = materials.add(Color::srgb(0.9, 0.5, 0.2));
+    for x in 0..CROWD_SIDE {
+        for z in 0..CROWD_SIDE {
+            let offset = (CROWD_SIDE as f32 - 1.0) / 2.0;
+            let position = vec3(x as f32 - offset, 0.0, z as f32 - offset) * 2.0;
+            commands.spawn((
+                CharacterController::default(),
+                Collider::cylinder(0.35, 1.8),
+                Transform::from_translation(position + Vec3::Y * 20.0),
+                CharacterLook::default(),
+                Wanderer {
+                    seed: x * CROWD_SIDE + z,
+                    ..default()
+                },
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Transform::from_xyz(0.0, 1.0, 0.0).looking_at(vec3(1.0, -2.0, -2.0), Vec3::Y),
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        SceneRoot(assets.load("maps/playground.glb#Scene0")),
+        RigidBody::Static,
+        ColliderConstructorHierarchy::new(ColliderConstructor::ConvexHullFromMesh),
+    ));
+}
+
+/// A very simple AI that walks in a random direction and picks a new one every now and then.
+#[derive(Component, Default)]
+struct Wanderer {
+    seed: u32,
+    next_turn: f32,
+    walking: bool,
+}
+
+impl Wanderer {
+    /// A cheap pseudo-random number in `0.0..1.0`, so that the example needs no `rand`.
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed = self.seed.wrapping_add(0x9e37_79b9);
+        (self.seed >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+fn wander(
+    mut npcs: Query<(&mut Wanderer, &mut AccumulatedInput, &mut CharacterLook)>,
+    time: Res<Time>,
+) {
+    npcs.par_iter_mut()
+        .for_each(|(mut wanderer, mut input, mut look)| {
+            wanderer.next_turn -= time.delta_secs();
+            if wanderer.next_turn <= 0.0 {
+                wanderer.next_turn = 1.0 + 3.0 * wanderer.random();
+                look.yaw = TAU * wanderer.random();
+                // Stand still every now and then
+                wanderer.walking = wanderer.random() > 0.2;
+                if wanderer.random() < 0.1 {
+                    input.jumped = Some(Stopwatch::new());
+                }
+            }
+            // The movement input is cleared after every tick, so keep feeding it
+            input.last_movement = wanderer.walking.then_some(Vec2::Y);
+        });
+}
+
+#[derive(Component, Default)]
+pub(crate) struct PlayerInput;
+
+fn capture_cursor(mut cursor: Single<&mut CursorOptions>) {
+    cursor.grab_mode = CursorGrabMode::Locked;
+    cursor.visible = false;
+}
+
+fn release_cursor(mut cursor: Single<&mut CursorOptions>) {
+    cursor.visible = true;
+    cursor.grab_mode = CursorGrabMode::None;
+}
@@ -50,6 +50,7 @@ fn main() -> AppExit {
             PhysicsPlugins::default(),
             EnhancedInputPlugin,
             AhoyPlugins::default(),
+            AhoyExtraPlugins::default(),
             TrenchBroomPlugins(
                 TrenchBroomConfig::new("bevy_ahoy")
                     .default_solid_scene_hooks(|| {
@@ -77,7 +78,6 @@ fn main() -> AppExit {
                 release_cursor.run_if(input_just_pressed(KeyCode::Escape)),
             ),
         )
-        .add_systems(FixedUpdate, move_trains)
         .add_observer(spawn_player)
         // NPC Stuff
         .add_input_context::<Npc>()
@@ -211,6 +211,10 @@ impl PlayerInput {
                     ActionSettings { consume_input: false, ..default() },
                     bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
                 ),
+                (
+                    Action::<Sprint>::new(),
+                    bindings![KeyCode::ShiftLeft, GamepadButton::LeftThumb],
+                ),
                 (
                     Action::<SwimUp>::new(),
                     ActionSettings { consume_input: false, ..default() },
@@ -322,82 +326,6 @@ enum CollisionLayer {
     Prop,
 }
 
-#[solid_class(base(Transform, Visibility))]
-#[derive(Default)]
-#[require(RigidBody::Kinematic, TransformInterpolation, GlobalTransform)]
-struct FuncTrain {
-    target: String,
-    speed: f32,
-    rotation: Vec3,
-}
-
-#[point_class(base(Transform, Visibility))]
-#[derive(Default)]
-#[require(GlobalTransform)]
-struct PathCorner {
-    #[class(must_set)]
-    targetname: String,
-    target: String,
-}
-
-fn move_trains(
-    mut trains: Query<(
-        &GlobalTransform,
-        &mut LinearVelocity,
-        &mut AngularVelocity,
-        &mut FuncTrain,
-    )>,
-    corners: Query<(&GlobalTransform, &PathCorner)>,
-) {
-    for (train_transform, mut train_vel, mut train_ang_vel, mut train) in &mut trains {
-        train_ang_vel.0 = train.rotation;
-        if train.target.is_empty() {
-            continue;
-        }
-        let Some((corner_transform, corner)) = corners
-            .iter()
-            .find(|(_, corner)| corner.targetname == train.target)
-        else {
-            error!("PathCorner not found for target: {}", train.target);
-            continue;
-        };
-        if train_transform
-            .translation()
-            .distance_squared(corner_transform.translation())
-            < 0.1
-        {
-            train.target = corner.target.clone();
-            continue;
-        }
-
-        let to_corner = corner_transform.translation() - train_transform.translation();
-        train_vel.0 = to_corner.normalize_or_zero() * train.speed;
-    }
-}
-
-#[solid_class(base(Transform, Visibility))]
-#[component(on_add = on_add_water)]
-pub struct Water {
-    speed: f32,
-}
-
-impl Default for Water {
-    fn default() -> Self {
-        Self { speed: 1.0 }
-    }
-}
-
-fn on_add_water(mut world: DeferredWorld, ctx: HookContext) {
-    if world.is_scene_world() {
-        return;
-    }
-    let speed = world.get::<Water>(ctx.entity).unwrap().speed;
-    world
-        .commands()
-        .entity(ctx.entity)
-        .insert(bevy_ahoy::prelude::Water { speed });
-}
-
 #[solid_class(base(Transform, Visibility))]
 #[component(on_add = on_add_ice)]
 #[derive(Default)]
@@ -413,7 +341,7 @@ fn on_add_ice(mut world: DeferredWorld, ctx: HookContext) {
     world
         .commands()
         .entity(ctx.entity)
-        .insert(Friction::new(friction));
+        .insert((Friction::new(friction), Slippery::default()));
 }
 
 //NPC Stuff
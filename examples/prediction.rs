@@ -0,0 +1,162 @@
+//! Shows how to correct a locally predicted character with authoritative state from a server.
+//!
+//! There is no real networking here: the "server" simply echoes the client's own predictions
+//! back with some latency. Press K to make the server knock the player back, which the client
+//! did not predict, so it has to reconcile.
+
+use std::collections::VecDeque;
+
+use avian3d::prelude::*;
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+use bevy_ahoy::{prelude::*, reconcile_character};
+use bevy_enhanced_input::prelude::*;
+
+use crate::util::ExampleUtilPlugin;
+
+mod util;
+
+/// How many ticks it takes for the server state to arrive.
+const LATENCY_TICKS: usize = 10;
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            PhysicsPlugins::default(),
+            EnhancedInputPlugin,
+            AhoyPlugins::default(),
+            AhoyExtraPlugins::default(),
+            ExampleUtilPlugin,
+        ))
+        .add_input_context::<PlayerInput>()
+        .init_resource::<FakeServer>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                capture_cursor.run_if(input_just_pressed(MouseButton::Left)),
+                release_cursor.run_if(input_just_pressed(KeyCode::Escape)),
+                request_knockback.run_if(input_just_pressed(KeyCode::KeyK)),
+            ),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            receive_server_state.after(AhoySystems::MoveCharacters),
+        )
+        .run()
+}
+
+fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+    let player = commands
+        .spawn((
+            CharacterController::default(),
+            // Record the predicted ticks so that they can be re-simulated on corrections
+            PredictionHistory::default(),
+            Collider::cylinder(0.7, 1.8),
+            Transform::from_xyz(0.0, 20.0, 0.0),
+            PlayerInput,
+            actions!(PlayerInput[
+                (
+                    Action::<Movement>::new(),
+                    DeadZone::default(),
+                    Bindings::spawn((
+                        Cardinal::wasd_keys(),
+                        Axial::left_stick()
+                    ))
+                ),
+                (
+                    Action::<Jump>::new(),
+                    bindings![KeyCode::Space,  GamepadButton::South],
+                ),
+                (
+                    Action::<Crouch>::new(),
+                    bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
+                ),
+                (
+                    Action::<RotateCamera>::new(),
+                    Bindings::spawn((
+                        Spawn((Binding::mouse_motion(), Scale::splat(0.07))),
+                        Axial::right_stick().with((Scale::splat(4.0), DeadZone::default())),
+                    ))
+                ),
+            ]),
+        ))
+        .id();
+
+    commands.spawn((
+        Camera3d::default(),
+        CharacterControllerCameraOf::new(player),
+    ));
+
+    commands.spawn((
+        Transform::from_xyz(0.0, 1.0, 0.0).looking_at(vec3(1.0, -2.0, -2.0), Vec3::Y),
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        SceneRoot(assets.load("maps/playground.glb#Scene0")),
+        RigidBody::Static,
+        ColliderConstructorHierarchy::new(ColliderConstructor::ConvexHullFromMesh),
+    ));
+}
+
+/// Stands in for the authoritative state a real server would send.
+#[derive(Resource, Default)]
+struct FakeServer {
+    in_flight: VecDeque<(u32, CharacterSnapshot)>,
+    knockback: bool,
+}
+
+fn request_knockback(mut server: ResMut<FakeServer>) {
+    server.knockback = true;
+}
+
+fn receive_server_state(
+    world: &mut World,
+    mut players: Local<QueryState<(Entity, &PredictionHistory), With<PlayerInput>>>,
+) -> Result {
+    let Ok((player, history)) = players.single(world) else {
+        return Ok(());
+    };
+    // The tick that was just simulated
+    let tick = history.tick.wrapping_sub(1);
+    let snapshot = CharacterSnapshot::capture(world, player).ok_or("player is not a character")?;
+
+    let mut server = world.resource_mut::<FakeServer>();
+    server.in_flight.push_back((tick, snapshot));
+    if server.in_flight.len() < LATENCY_TICKS {
+        return Ok(());
+    }
+    let Some((tick, mut authoritative)) = server.in_flight.pop_front() else {
+        return Ok(());
+    };
+    if !core::mem::take(&mut server.knockback) {
+        // A real client would compare the authoritative state with `history.get(tick + 1)`
+        // and only reconcile when they differ.
+        return Ok(());
+    }
+    // The server knocked us back, but our predictions after `tick` don't know about that yet.
+    server.in_flight.clear();
+    authoritative.velocity.0 += vec3(0.0, 8.0, 12.0);
+    reconcile_character(world, player, tick, &authoritative)
+}
+
+#[derive(Component, Default)]
+pub(crate) struct PlayerInput;
+
+fn capture_cursor(mut cursor: Single<&mut CursorOptions>) {
+    cursor.grab_mode = CursorGrabMode::Locked;
+    cursor.visible = false;
+}
+
+fn release_cursor(mut cursor: Single<&mut CursorOptions>) {
+    cursor.visible = true;
+    cursor.grab_mode = CursorGrabMode::None;
+}
@@ -113,11 +113,17 @@ fn setup(mut commands: Commands, assets: Res<AssetServer>) {
 #[require(
     PlayerInput,
     CharacterController {
-        acceleration_hz: 10.0,
-        air_acceleration_hz: 150.0,
         speed: 6.0,
         gravity: 23.0,
-        friction_hz: 4.0,
+        ground: GroundConfig {
+            acceleration_hz: 10.0,
+            friction_hz: 4.0,
+            ..default()
+        },
+        air: AirConfig {
+            acceleration_hz: 150.0,
+            ..default()
+        },
         ..default()
     },
     RigidBody::Kinematic,
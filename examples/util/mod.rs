@@ -7,12 +7,11 @@ use bevy::{
     camera::Exposure,
     light::{CascadeShadowConfigBuilder, DirectionalLightShadowMap, light_consts::lux},
     pbr::{Atmosphere, ScatteringMedium},
-    platform::collections::HashSet,
     post_process::bloom::Bloom,
     prelude::*,
     window::{CursorGrabMode, CursorOptions},
 };
-use bevy_ahoy::{CharacterControllerOutput, CharacterControllerState, prelude::*};
+use bevy_ahoy::prelude::*;
 use bevy_ecs::world::FilteredEntityRef;
 use bevy_enhanced_input::prelude::{Release, *};
 use bevy_fix_cursor_unlock_web::{FixPointerUnlockPlugin, ForceUnlockCursor};
@@ -31,105 +30,29 @@ impl Plugin for ExampleUtilPlugin {
         .add_systems(Startup, (setup_ui, spawn_crosshair))
         .add_systems(
             Update,
-            (
-                update_debug_text,
-                tweak_materials,
-                generate_mipmaps::<StandardMaterial>,
-            ),
+            (tweak_materials, generate_mipmaps::<StandardMaterial>),
         )
         .add_observer(reset_player)
         .add_observer(tweak_camera)
         .add_observer(tweak_directional_light)
-        .add_observer(toggle_debug)
         .add_observer(unlock_cursor_web)
         .insert_resource(DirectionalLightShadowMap { size: 4096 })
         .insert_resource(GlobalAmbientLight::NONE)
         .add_systems(Update, turn_sun)
         .add_input_context::<DebugInput>();
+        #[cfg(feature = "debug")]
+        app.add_observer(toggle_debug)
+            .add_observer(target_debug_overlay);
     }
 }
 
-fn update_debug_text(
-    mut text: Single<&mut Text, With<DebugText>>,
-    kcc: Single<
-        (
-            &CharacterControllerState,
-            &CharacterControllerOutput,
-            &LinearVelocity,
-            &CollidingEntities,
-            &ColliderAabb,
-        ),
-        (With<CharacterController>, With<CharacterControllerCamera>),
-    >,
-    camera: Single<&Transform, With<Camera>>,
-    names: Query<NameOrEntity>,
-) {
-    let (state, output, velocity, colliding_entities, aabb) = kcc.into_inner();
-    let velocity = **velocity;
-    let speed = velocity.length();
-    let horizontal_speed = velocity.xz().length();
-    let camera_position = camera.translation;
-    let collisions = names
-        .iter_many(
-            output
-                .touching_entities
-                .iter()
-                .map(|e| e.entity)
-                .collect::<HashSet<_>>(),
-        )
-        .map(|name| {
-            name.name
-                .map(|n| format!("{} ({})", name.entity, n))
-                .unwrap_or_else(|| format!("{}", name.entity))
-        })
-        .collect::<Vec<_>>();
-    let real_collisions = names
-        .iter_many(colliding_entities.iter())
-        .map(|name| {
-            name.name
-                .map(|n| format!("{} ({})", name.entity, n))
-                .unwrap_or_else(|| format!("{}", name.entity))
-        })
-        .collect::<Vec<_>>();
-    let ground = state
-        .grounded
-        .and_then(|ground| names.get(ground.entity).ok())
-        .map(|name| {
-            name.name
-                .map(|n| format!("{} ({})", name.entity, n))
-                .unwrap_or(format!("{}", name.entity))
-        });
-    text.0 = format!(
-        "Speed: {speed:.3}\nHorizontal Speed: {horizontal_speed:.3}\nVelocity: [{:.3}, {:.3}, {:.3}]\nCamera Position: [{:.3}, {:.3}, {:.3}]\nCollider Aabb:\n  min:[{:.3}, {:.3}, {:.3}]\n  max:[{:.3}, {:.3}, {:.3}]\nReal Collisions: {:#?}\nCollisions: {:#?}\nGround: {:?}",
-        velocity.x,
-        velocity.y,
-        velocity.z,
-        camera_position.x,
-        camera_position.y,
-        camera_position.z,
-        aabb.min.x,
-        aabb.min.y,
-        aabb.min.z,
-        aabb.max.x,
-        aabb.max.y,
-        aabb.max.z,
-        real_collisions,
-        collisions,
-        ground
-    );
-}
-
-#[derive(Component, Reflect, Debug)]
-#[reflect(Component)]
-struct DebugText;
-
 fn setup_ui(mut commands: Commands) {
-    commands.spawn((
-        Node::default(),
-        Text::default(),
-        Visibility::Hidden,
-        DebugText,
-    ));
+    let mut controls =
+        "Controls:\nWASD: move\nSpace: jump\nCtrl: crouch\nEsc: free mouse\nR: reset position"
+            .to_string();
+    if cfg!(feature = "debug") {
+        controls.push_str("\nBacktick: Toggle Debug Menu");
+    }
     commands.spawn((
         Node {
             justify_self: JustifySelf::End,
@@ -138,9 +61,7 @@ fn setup_ui(mut commands: Commands) {
             padding: UiRect::all(px(10.0)),
             ..default()
         },
-        Text::new(
-            "Controls:\nWASD: move\nSpace: jump\nCtrl: crouch\nEsc: free mouse\nR: reset position\nBacktick: Toggle Debug Menu",
-        ),
+        Text::new(controls),
     ));
     commands.spawn((
         DebugInput,
@@ -174,14 +95,15 @@ fn reset_player(_fire: On<Fire<Reset>>, mut commands: Commands) {
     commands.run_system_cached(reset_player_inner);
 }
 
-fn toggle_debug(
-    _fire: On<Fire<ToggleDebug>>,
-    mut visibility: Single<&mut Visibility, With<DebugText>>,
-) {
-    **visibility = match **visibility {
-        Visibility::Hidden => Visibility::Inherited,
-        _ => Visibility::Hidden,
-    };
+#[cfg(feature = "debug")]
+fn toggle_debug(_fire: On<Fire<ToggleDebug>>, mut overlay: ResMut<AhoyDebugOverlay>) {
+    overlay.visible = !overlay.visible;
+}
+
+/// Shows the player in the debug overlay
+#[cfg(feature = "debug")]
+fn target_debug_overlay(add: On<Add, CharacterControllerCamera>, mut commands: Commands) {
+    commands.entity(add.entity).insert(DebugOverlayTarget);
 }
 
 fn reset_player_inner(
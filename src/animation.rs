@@ -0,0 +1,204 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+
+use crate::{
+    CharacterControllerState,
+    kcc::{forward, right},
+    prelude::*,
+};
+
+/// How long it takes [`CharacterAnimationState::crouch_fraction`] to go from standing to
+/// crouching, in seconds.
+const CROUCH_BLEND_TIME: f32 = 0.2;
+
+/// How far a character without [`Footsteps`] walks between two [`AnimationCueKind::Step`]s,
+/// relative to its height.
+const STRIDE_PER_HEIGHT: f32 = 0.8;
+
+/// Updates the [`CharacterAnimationState`] of every character after it moved.
+pub struct AhoyAnimationPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_required_components::<CharacterController, CharacterAnimationState>()
+            .add_systems(
+                self.schedule,
+                (update_animation_state, update_slope_tilt).after(AhoySystems::MoveCharacters),
+            )
+            .add_observer(cue_water_enter);
+    }
+}
+
+/// Triggered at notable moments of a character's movement, so that animations and sounds can be
+/// started in sync with it, e.g. by calling `AnimationPlayer::play` in an observer.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct AnimationCue {
+    /// The character the cue is for.
+    pub entity: Entity,
+    pub kind: AnimationCueKind,
+}
+
+#[derive(Clone, Copy, Reflect, PartialEq, Eq, Debug)]
+pub enum AnimationCueKind {
+    /// The character jumped.
+    JumpStart,
+    /// The character touched the ground after being in the air.
+    Land,
+    /// The character took a step while walking on the ground.
+    Step,
+    /// The character entered water.
+    WaterEnter,
+}
+
+/// Values for driving animation graphs, updated every tick after the character moved.
+#[derive(Component, Clone, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct CharacterAnimationState {
+    /// The horizontal speed, relative to [`CharacterController::speed`]. `1.0` is full running
+    /// speed, but it can go higher, e.g. when bunny hopping.
+    pub planar_speed: f32,
+    /// The vertical speed in meters per second. Positive when moving up.
+    pub vertical_speed: f32,
+    pub grounded: bool,
+    /// How far the character is into crouching, from `0.0` for standing to `1.0` for crouching.
+    /// Blends smoothly towards [`CharacterControllerState::crouch_amount`], which includes partial
+    /// heights under low ceilings.
+    pub crouch_fraction: f32,
+    /// How hard the character turns or strafes sideways, from `-1.0` for left to `1.0` for right.
+    pub lean: f32,
+    /// The horizontal velocity relative to the look direction and [`CharacterController::speed`],
+    /// with `x` pointing right and `y` pointing forward. Use this to pick strafing animations.
+    pub strafe: Vec2,
+    #[reflect(ignore)]
+    last_velocity: Vec3,
+    #[reflect(ignore)]
+    distance_since_step: f32,
+}
+
+/// Tilts with the ground the character stands on, e.g. for skateboards or four-legged animals.
+///
+/// The character itself stays upright. Rotate a child mesh by [`Self::rotation`] to tilt it.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SlopeTilt {
+    /// The steepest tilt, in radians.
+    pub max_tilt: f32,
+    /// How fast the tilt follows the ground, in hertz. Higher is snappier.
+    pub smoothing_hz: f32,
+    /// The smoothed up direction of the ground. Points straight up while in the air.
+    pub up: Dir3,
+}
+
+impl Default for SlopeTilt {
+    fn default() -> Self {
+        Self {
+            max_tilt: 30.0_f32.to_radians(),
+            smoothing_hz: 10.0,
+            up: Dir3::Y,
+        }
+    }
+}
+
+impl SlopeTilt {
+    /// The rotation from upright to [`Self::up`].
+    pub fn rotation(&self) -> Quat {
+        Quat::from_rotation_arc(Vec3::Y, *self.up)
+    }
+}
+
+fn update_animation_state(
+    mut characters: Query<
+        (
+            Entity,
+            &mut CharacterAnimationState,
+            &CharacterController,
+            &CharacterControllerState,
+            &LinearVelocity,
+            Option<&Footsteps>,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+    if dt == 0.0 {
+        return;
+    }
+    for (entity, mut anim, cfg, state, velocity, footsteps) in &mut characters {
+        let planar_velocity = velocity.with_y(0.0);
+        let right = right(state.orientation).with_y(0.0).normalize_or_zero();
+        let forward = forward(state.orientation).with_y(0.0).normalize_or_zero();
+        let speed = cfg.speed.max(f32::EPSILON);
+
+        anim.planar_speed = planar_velocity.length() / speed;
+        anim.vertical_speed = velocity.y;
+        anim.grounded = state.grounded.is_some();
+        let crouch_target = state.crouch_amount;
+        let max_step = dt / CROUCH_BLEND_TIME;
+        anim.crouch_fraction += (crouch_target - anim.crouch_fraction).clamp(-max_step, max_step);
+        anim.strafe = vec2(planar_velocity.dot(right), planar_velocity.dot(forward)) / speed;
+
+        // Lean into the sideways acceleration, relative to the strongest possible acceleration
+        let acceleration = (planar_velocity - anim.last_velocity) / dt;
+        let max_acceleration = speed * cfg.ground.acceleration_hz.max(f32::EPSILON);
+        anim.lean = (acceleration.dot(right) / max_acceleration).clamp(-1.0, 1.0);
+        anim.last_velocity = planar_velocity;
+
+        // The velocity is relative to the platform the character stands on, so standing still
+        // on a moving platform takes no steps
+        let (stride, min_speed) = footsteps.map_or(
+            (cfg.standing_view_height * STRIDE_PER_HEIGHT, 0.0),
+            |footsteps| (footsteps.stride, footsteps.min_speed),
+        );
+        let planar_speed = planar_velocity.length();
+        if !anim.grounded {
+            // Start with a fresh stride after landing
+            anim.distance_since_step = 0.0;
+        } else if planar_speed >= min_speed {
+            anim.distance_since_step += planar_speed * dt;
+            if anim.distance_since_step >= stride {
+                anim.distance_since_step = 0.0;
+                commands.trigger(AnimationCue {
+                    entity,
+                    kind: AnimationCueKind::Step,
+                });
+            }
+        }
+    }
+}
+
+fn cue_water_enter(changed: On<WaterLevelChanged>, mut commands: Commands) {
+    if changed.is_entering() {
+        commands.trigger(AnimationCue {
+            entity: changed.entity,
+            kind: AnimationCueKind::WaterEnter,
+        });
+    }
+}
+
+fn update_slope_tilt(
+    mut characters: Query<
+        (&mut SlopeTilt, &CharacterControllerState),
+        Without<CharacterControllerDisabled>,
+    >,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for (mut tilt, state) in &mut characters {
+        let ground_up = state
+            .grounded
+            .and_then(|ground| Dir3::new(ground.normal1).ok())
+            .unwrap_or(Dir3::Y);
+        // Limit the tilt by rotating the ground's up back towards straight up
+        let angle = ground_up.angle_between(Vec3::Y);
+        let target = if angle > tilt.max_tilt {
+            Dir3::Y.slerp(ground_up, tilt.max_tilt / angle)
+        } else {
+            ground_up
+        };
+        let t = 1.0 - (-tilt.smoothing_hz * dt).exp();
+        tilt.up = tilt.up.slerp(target, t);
+    }
+}
@@ -0,0 +1,255 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::time::Duration;
+use std::borrow::Cow;
+
+use crate::{
+    CharacterControllerOutput, CharacterControllerState,
+    kcc::{WalkableAngles, on_collider_or_body},
+    prelude::*,
+};
+
+/// Triggers [`MovementSound`]s for characters with [`MovementSounds`].
+pub struct AhoyAudioPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_movement_sounds.after(AhoySystems::MoveCharacters),
+        )
+        .add_observer(sound_animation_cues)
+        .add_observer(sound_landings);
+    }
+}
+
+/// The material of a surface, e.g. `"metal"` or `"grass"`, for picking footstep sounds or
+/// landing particles.
+///
+/// Insert this on a collider or on its rigid body. The collider takes precedence.
+#[derive(Component, Clone, Reflect, Debug, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct SurfaceMaterial(pub Cow<'static, str>);
+
+impl SurfaceMaterial {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+}
+
+/// Makes a character trigger [`MovementSound`]s, and configures how often they can be triggered.
+///
+/// Every kind of sound is throttled on its own, so that e.g. bouncing down a staircase does not
+/// play a burst of landings.
+#[derive(Component, Clone, Reflect, Debug)]
+#[reflect(Component)]
+pub struct MovementSounds {
+    /// The shortest time between two sounds of the same kind.
+    pub min_interval: Duration,
+    /// The impact speed at which a landing has an intensity of `1.0`, in meters per second.
+    pub hard_landing_speed: f32,
+    /// How far a character swims between two [`MovementSoundKind::WaterStroke`]s, in meters.
+    pub stroke_distance: f32,
+    /// The horizontal speed above which a character sliding along a steep surface counts as
+    /// sliding, in meters per second.
+    pub min_slide_speed: f32,
+    #[reflect(ignore)]
+    last_played: [Option<Duration>; 4],
+    #[reflect(ignore)]
+    distance_since_stroke: f32,
+    #[reflect(ignore)]
+    sliding: bool,
+}
+
+impl Default for MovementSounds {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_millis(150),
+            hard_landing_speed: 15.0,
+            stroke_distance: 1.5,
+            min_slide_speed: 2.0,
+            last_played: default(),
+            distance_since_stroke: 0.0,
+            sliding: false,
+        }
+    }
+}
+
+impl MovementSounds {
+    /// Whether a sound of `kind` may be played at `now`. Marks it as played if so.
+    fn throttle(&mut self, kind: &MovementSoundKind, now: Duration) -> bool {
+        let slot = match kind {
+            MovementSoundKind::Footstep { .. } => 0,
+            MovementSoundKind::Land { .. } => 1,
+            MovementSoundKind::Jump => 2,
+            MovementSoundKind::WaterStroke => 3,
+            // Slides are only triggered when they change, so they never need throttling
+            MovementSoundKind::SlideStart | MovementSoundKind::SlideStop => return true,
+        };
+        let last_played = &mut self.last_played[slot];
+        if last_played.is_some_and(|last| now.saturating_sub(last) < self.min_interval) {
+            return false;
+        }
+        *last_played = Some(now);
+        true
+    }
+}
+
+/// Triggered when a character with [`MovementSounds`] should make a sound, e.g. to play it with
+/// `bevy_audio` or `bevy_kira_audio` at [`Self::position`].
+#[derive(EntityEvent, Clone, Debug)]
+pub struct MovementSound {
+    /// The character making the sound.
+    pub entity: Entity,
+    pub kind: MovementSoundKind,
+    /// Where the sound comes from, expressed in world space.
+    pub position: Vec3,
+}
+
+#[derive(Clone, Reflect, PartialEq, Debug)]
+pub enum MovementSoundKind {
+    /// The character took a step on the ground.
+    Footstep {
+        /// The material of the ground, if it has a [`SurfaceMaterial`].
+        material: Option<SurfaceMaterial>,
+    },
+    /// The character touched the ground after being in the air.
+    Land {
+        /// How hard the character landed, from `0.0` to `1.0`. See
+        /// [`MovementSounds::hard_landing_speed`].
+        intensity: f32,
+        /// The material of the ground, if it has a [`SurfaceMaterial`].
+        material: Option<SurfaceMaterial>,
+    },
+    /// The character jumped.
+    Jump,
+    /// The character swam some distance.
+    WaterStroke,
+    /// The character started sliding along a surface too steep to walk on, e.g. while surfing.
+    /// Start a looping sound here.
+    SlideStart,
+    /// The character stopped sliding. Stop the looping sound here.
+    SlideStop,
+}
+
+/// Finds the [`SurfaceMaterial`] of `collider`, falling back to the one of its rigid body.
+pub(crate) fn surface_material(
+    collider: Entity,
+    materials: &Query<&SurfaceMaterial>,
+    colliders: &Query<&ColliderOf>,
+) -> Option<SurfaceMaterial> {
+    on_collider_or_body(collider, materials, colliders).cloned()
+}
+
+fn sound_animation_cues(
+    cue: On<AnimationCue>,
+    mut characters: Query<(&mut MovementSounds, &Transform, &CharacterControllerState)>,
+    materials: Query<&SurfaceMaterial>,
+    colliders: Query<&ColliderOf>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Ok((mut sounds, transform, state)) = characters.get_mut(cue.entity) else {
+        return;
+    };
+    let kind = match cue.kind {
+        AnimationCueKind::Step => MovementSoundKind::Footstep {
+            material: state
+                .grounded
+                .and_then(|ground| surface_material(ground.entity, &materials, &colliders)),
+        },
+        AnimationCueKind::JumpStart => MovementSoundKind::Jump,
+        // Landings are sounded from the `LandingImpact`, which knows how hard the character landed
+        AnimationCueKind::Land | AnimationCueKind::WaterEnter => return,
+    };
+    if !sounds.throttle(&kind, time.elapsed()) {
+        return;
+    }
+    commands.trigger(MovementSound {
+        entity: cue.entity,
+        kind,
+        position: transform.translation,
+    });
+}
+
+fn sound_landings(
+    impact: On<LandingImpact>,
+    mut characters: Query<&mut MovementSounds>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Ok(mut sounds) = characters.get_mut(impact.entity) else {
+        return;
+    };
+    let kind = MovementSoundKind::Land {
+        intensity: (impact.speed / sounds.hard_landing_speed.max(f32::EPSILON)).clamp(0.0, 1.0),
+        material: impact.material.clone(),
+    };
+    if !sounds.throttle(&kind, time.elapsed()) {
+        return;
+    }
+    commands.trigger(MovementSound {
+        entity: impact.entity,
+        kind,
+        position: impact.point,
+    });
+}
+
+fn update_movement_sounds(
+    mut characters: Query<
+        (
+            Entity,
+            &mut MovementSounds,
+            &Transform,
+            &LinearVelocity,
+            &CharacterController,
+            &CharacterControllerState,
+            &CharacterControllerOutput,
+            &WaterState,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+    walkable_angles: WalkableAngles,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut sounds, transform, velocity, cfg, state, output, water) in &mut characters {
+        let mut kinds = Vec::new();
+        if water.level > WaterLevel::Feet {
+            sounds.distance_since_stroke += velocity.length() * time.delta_secs();
+            if sounds.distance_since_stroke >= sounds.stroke_distance {
+                sounds.distance_since_stroke = 0.0;
+                kinds.push(MovementSoundKind::WaterStroke);
+            }
+        } else {
+            sounds.distance_since_stroke = 0.0;
+        }
+
+        let on_steep_surface = state.grounded.is_none()
+            && output.touching_entities.iter().any(|touch| {
+                touch.normal.y > 0.0
+                    && touch.normal.y < walkable_angles.min_walk_cos(touch.entity, cfg)
+            });
+        let sliding = on_steep_surface && velocity.xz().length() >= sounds.min_slide_speed;
+        if sliding != sounds.sliding {
+            sounds.sliding = sliding;
+            kinds.push(if sliding {
+                MovementSoundKind::SlideStart
+            } else {
+                MovementSoundKind::SlideStop
+            });
+        }
+
+        for kind in kinds {
+            if !sounds.throttle(&kind, time.elapsed()) {
+                continue;
+            }
+            commands.trigger(MovementSound {
+                entity,
+                kind,
+                position: transform.translation,
+            });
+        }
+    }
+}
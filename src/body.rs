@@ -0,0 +1,104 @@
+use bevy_ecs::{lifecycle::HookContext, relationship::Relationship, world::DeferredWorld};
+
+use crate::prelude::*;
+
+/// Moves [`CharacterControllerBodyOf`]s along with their characters.
+///
+/// Needs [`AhoyAnimationPlugin`](crate::AhoyAnimationPlugin) to lower bodies while crouching.
+pub struct AhoyBodyPlugin;
+
+impl Plugin for AhoyBodyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            RunFixedMainLoop,
+            sync_body_transform.after(TransformEasingSystems::UpdateEasingTick),
+        );
+    }
+}
+
+/// Pins a visual entity, e.g. a render mesh, to a character without making it a child of the
+/// character.
+///
+/// The body follows the interpolated position and the rotation of the character every frame, and
+/// is lowered while the character crouches.
+#[derive(Component, Clone, Copy, Debug)]
+#[relationship(relationship_target = CharacterControllerBody)]
+#[require(Transform)]
+#[component(on_add = Self::on_add)]
+pub struct CharacterControllerBodyOf {
+    #[relationship]
+    pub character_controller: Entity,
+    /// Where the body rests, relative to the character.
+    pub offset: Vec3,
+    /// How far the body is lowered while the character is fully crouched.
+    pub crouch_offset: f32,
+    /// How quickly the body catches up with the character. Higher values are snappier. `None`
+    /// follows the character exactly.
+    pub smoothing: Option<f32>,
+}
+
+impl CharacterControllerBodyOf {
+    pub fn new(character_controller: Entity) -> Self {
+        Self {
+            character_controller,
+            offset: Vec3::ZERO,
+            crouch_offset: 0.0,
+            smoothing: None,
+        }
+    }
+
+    fn on_add(mut world: DeferredWorld, ctx: HookContext) {
+        let Some(body) = world.get::<Self>(ctx.entity).copied() else {
+            return;
+        };
+        let Some(kcc_transform) = world.get::<Transform>(body.get()).copied() else {
+            return;
+        };
+        let Some(mut body_transform) = world.get_mut::<Transform>(ctx.entity) else {
+            return;
+        };
+        body_transform.translation = kcc_transform.transform_point(body.offset);
+        body_transform.rotation = kcc_transform.rotation;
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+#[relationship_target(relationship = CharacterControllerBodyOf)]
+pub struct CharacterControllerBody(Entity);
+
+impl CharacterControllerBody {
+    pub fn get(self) -> Entity {
+        self.0
+    }
+}
+
+fn sync_body_transform(
+    mut bodies: Query<(&mut Transform, &CharacterControllerBodyOf)>,
+    kccs: Query<(&Transform, Option<&CharacterAnimationState>), Without<CharacterControllerBodyOf>>,
+    time: Res<Time>,
+) {
+    for (mut body_transform, body) in &mut bodies {
+        let Ok((kcc_transform, anim)) = kccs.get(body.get()) else {
+            continue;
+        };
+        let crouch_fraction = anim.map_or(0.0, |anim| anim.crouch_fraction);
+        let target = kcc_transform.transform_point(body.offset)
+            - Vec3::Y * body.crouch_offset * crouch_fraction;
+        match body.smoothing {
+            Some(decay_rate) => {
+                body_transform
+                    .translation
+                    .smooth_nudge(&target, decay_rate, time.delta_secs());
+                body_transform.rotation.smooth_nudge(
+                    &kcc_transform.rotation,
+                    decay_rate,
+                    time.delta_secs(),
+                );
+            }
+            None => {
+                body_transform.translation = target;
+                body_transform.rotation = kcc_transform.rotation;
+            }
+        }
+    }
+}
@@ -20,9 +20,13 @@ impl Plugin for AhoyCameraPlugin {
         )
         .add_systems(
             Update,
-            copy_character_look_to_camera.after(spin_character_look),
+            (
+                copy_character_look_to_camera.after(spin_character_look),
+                update_viewmodels.after(copy_character_look_to_camera),
+            ),
         )
-        .add_observer(rotate_camera);
+        .add_observer(rotate_camera)
+        .add_observer(punch_camera);
     }
 }
 
@@ -78,17 +82,77 @@ impl CharacterControllerCamera {
     }
 }
 
+/// Attaches a viewmodel, e.g. first-person arms or a weapon, to a [`CharacterControllerCameraOf`].
+///
+/// The viewmodel follows the camera, lags behind its rotation and the character's velocity, and
+/// bobs while the character walks.
+#[derive(Component, Clone, Copy, Debug)]
+#[relationship(relationship_target = Viewmodels)]
+#[require(Transform, ViewmodelSway)]
+pub struct ViewmodelOf {
+    #[relationship]
+    pub camera: Entity,
+    /// Where the viewmodel rests, relative to the camera.
+    pub offset: Vec3,
+    /// How far the viewmodel lags behind the camera's rotation, as a fraction of the rotation.
+    pub rotational_sway: f32,
+    /// How far the viewmodel lags behind the character's velocity, in meters per meter per
+    /// second.
+    pub positional_sway: f32,
+    /// The maximum distance the viewmodel lags behind.
+    pub max_positional_sway: f32,
+    /// How quickly the viewmodel catches up again. Higher values are snappier.
+    pub sway_decay_rate: f32,
+    /// How far the viewmodel bobs while walking at full speed.
+    pub bob_amplitude: f32,
+    /// How many bobs happen per meter walked.
+    pub bob_frequency: f32,
+}
+
+impl ViewmodelOf {
+    pub fn new(camera: Entity) -> Self {
+        Self {
+            camera,
+            offset: vec3(0.25, -0.25, -0.5),
+            rotational_sway: 0.5,
+            positional_sway: 0.004,
+            max_positional_sway: 0.05,
+            sway_decay_rate: 12.0,
+            bob_amplitude: 0.015,
+            bob_frequency: 0.35,
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+#[relationship_target(relationship = ViewmodelOf)]
+pub struct Viewmodels(Vec<Entity>);
+
+/// The current sway and bob of a [`ViewmodelOf`].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct ViewmodelSway {
+    last_camera_rotation: Option<Quat>,
+    /// The yaw and pitch lagging behind the camera.
+    rotation: Vec2,
+    /// The translation lagging behind the velocity, relative to the camera.
+    translation: Vec3,
+    bob_phase: f32,
+}
+
 pub(crate) fn sync_camera_transform(
     mut cameras: Query<
         (&mut Transform, &CharacterControllerCameraOf),
         (Without<CharacterControllerState>,),
     >,
-    kccs: Query<(
-        &Transform,
-        &CharacterController,
-        &CharacterControllerState,
-        &CharacterControllerDerivedProps,
-    )>,
+    kccs: Query<
+        (
+            &Transform,
+            &CharacterController,
+            &CharacterControllerState,
+            &CharacterControllerDerivedProps,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
     time: Res<Time>,
 ) {
     // TODO: DIY TransformHelper to use current global transform.
@@ -96,20 +160,15 @@ pub(crate) fn sync_camera_transform(
     // Can't use TransformHelper directly: access conflict with &mut Transform
     for (mut camera_transform, camera) in cameras.iter_mut() {
         if let Ok((kcc_transform, cfg, state, derived)) = kccs.get(camera.character_controller) {
-            let height = derived
+            let feet = derived
                 // changing the collider does not change the transform, so to get the correct position for the feet,
                 // we need to use the collider we spawned with.
                 .standing_collider
                 .aabb(Vec3::default(), Rotation::default())
-                .size()
+                .min
                 .y;
-            let view_height = if state.crouching {
-                cfg.crouch_view_height
-            } else {
-                cfg.standing_view_height
-            };
-            let new_translation =
-                kcc_transform.translation + Vec3::Y * (-height / 2.0 + view_height);
+            let view_height = cfg.view_height(state);
+            let new_translation = kcc_transform.translation + Vec3::Y * (feet + view_height);
             camera_transform.translation.x = new_translation.x;
             camera_transform.translation.z = new_translation.z;
             if !camera.enable_smoothing {
@@ -142,7 +201,10 @@ pub(crate) fn sync_camera_transform(
 }
 
 fn copy_camera_to_character_look(
-    mut character_looks: Query<(&CharacterControllerCamera, &mut CharacterLook)>,
+    mut character_looks: Query<
+        (&CharacterControllerCamera, &mut CharacterLook),
+        Without<CharacterControllerDisabled>,
+    >,
     transforms: Query<&Transform>,
 ) {
     for (camera, mut character_look) in &mut character_looks {
@@ -155,7 +217,10 @@ fn copy_camera_to_character_look(
 }
 
 fn copy_character_look_to_camera(
-    cameras: Query<(&CharacterLook, &CharacterControllerCamera)>,
+    cameras: Query<
+        (&CharacterLook, &CharacterControllerCamera),
+        Without<CharacterControllerDisabled>,
+    >,
     mut transforms: Query<&mut Transform>,
 ) {
     for (character_look, camera) in &cameras {
@@ -168,7 +233,7 @@ fn copy_character_look_to_camera(
 
 fn rotate_camera(
     rotate: On<Fire<RotateCamera>>,
-    cameras: Query<&CharacterControllerCamera>,
+    cameras: Query<&CharacterControllerCamera, Without<CharacterControllerDisabled>>,
     mut transforms: Query<&mut Transform>,
 ) {
     let Ok(camera) = cameras.get(rotate.context) else {
@@ -187,3 +252,82 @@ fn rotate_camera(
     transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
 }
 
+/// Kicks the camera along with the [`CharacterLook`], as the camera is copied to the look again
+/// before the next tick.
+fn punch_camera(
+    recoil: On<Recoil>,
+    cameras: Query<&CharacterControllerCamera, Without<CharacterControllerDisabled>>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let Ok(camera) = cameras.get(recoil.entity) else {
+        return;
+    };
+    let Ok(mut transform) = transforms.get_mut(camera.get()) else {
+        return;
+    };
+    let (mut yaw, mut pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+    yaw += recoil.view_punch.x;
+    pitch = (pitch + recoil.view_punch.y).clamp(-TAU / 4.0 + 0.01, TAU / 4.0 - 0.01);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+}
+
+fn update_viewmodels(
+    mut viewmodels: Query<(&ViewmodelOf, &mut ViewmodelSway, &mut Transform)>,
+    cameras: Query<(&Transform, &CharacterControllerCameraOf), Without<ViewmodelOf>>,
+    kccs: Query<(
+        &LinearVelocity,
+        &CharacterController,
+        &CharacterControllerState,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for (viewmodel, mut sway, mut transform) in &mut viewmodels {
+        let Ok((camera_transform, camera)) = cameras.get(viewmodel.camera) else {
+            continue;
+        };
+        let camera_rotation = camera_transform.rotation;
+
+        // Lag behind the camera's rotation
+        let last_rotation = sway
+            .last_camera_rotation
+            .replace(camera_rotation)
+            .unwrap_or(camera_rotation);
+        let (yaw, pitch, _) = camera_rotation.to_euler(EulerRot::YXZ);
+        let (last_yaw, last_pitch, _) = last_rotation.to_euler(EulerRot::YXZ);
+        // Wrap the yaw delta, so that turning past a half rotation doesn't flip the sway
+        let yaw_delta = (yaw - last_yaw + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0;
+        sway.rotation -= vec2(yaw_delta, pitch - last_pitch) * viewmodel.rotational_sway;
+        sway.rotation = sway.rotation.clamp_length_max(TAU / 16.0);
+        sway.rotation
+            .smooth_nudge(&Vec2::ZERO, viewmodel.sway_decay_rate, dt);
+
+        // Lag behind the velocity and bob while walking
+        let mut bob = Vec3::ZERO;
+        if let Ok((velocity, cfg, state)) = kccs.get(camera.character_controller) {
+            let local_velocity = camera_rotation.inverse() * velocity.0;
+            let target = (-local_velocity * viewmodel.positional_sway)
+                .clamp_length_max(viewmodel.max_positional_sway);
+            sway.translation
+                .smooth_nudge(&target, viewmodel.sway_decay_rate, dt);
+
+            let planar_speed = velocity.xz().length();
+            if state.grounded.is_some() {
+                sway.bob_phase =
+                    (sway.bob_phase + planar_speed * viewmodel.bob_frequency * TAU * dt) % TAU;
+            }
+            let intensity = (planar_speed / cfg.speed.max(f32::EPSILON)).min(1.0);
+            bob = vec3(
+                sway.bob_phase.sin(),
+                -(2.0 * sway.bob_phase).sin().abs(),
+                0.0,
+            ) * viewmodel.bob_amplitude
+                * intensity;
+        }
+
+        transform.translation = camera_transform.translation
+            + camera_rotation * (viewmodel.offset + sway.translation + bob);
+        transform.rotation = camera_rotation
+            * Quat::from_euler(EulerRot::YXZ, sway.rotation.x, sway.rotation.y, 0.0);
+    }
+}
@@ -0,0 +1,104 @@
+use bevy_ecs::query::Has;
+use bevy_ui::prelude::*;
+
+use crate::{CharacterControllerDisabled, CharacterControllerState, prelude::*};
+
+/// Shows a text overlay with the movement state of the character marked with [`DebugOverlayTarget`].
+///
+/// Toggle it with [`AhoyDebugOverlay::visible`].
+pub struct AhoyDebugPlugin;
+
+impl Plugin for AhoyDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AhoyDebugOverlay>()
+            .add_systems(Startup, spawn_debug_overlay)
+            .add_systems(Update, update_debug_overlay);
+    }
+}
+
+/// Settings of the debug overlay.
+#[derive(Resource, Clone, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct AhoyDebugOverlay {
+    pub visible: bool,
+}
+
+/// Selects the character whose state is shown in the debug overlay.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct DebugOverlayTarget;
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+fn spawn_debug_overlay(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            display: Display::None,
+            ..default()
+        },
+        Text::default(),
+        DebugOverlayText,
+    ));
+}
+
+fn update_debug_overlay(
+    overlay: Res<AhoyDebugOverlay>,
+    mut texts: Query<(&mut Text, &mut Node), With<DebugOverlayText>>,
+    characters: Query<
+        (
+            &CharacterControllerState,
+            &LinearVelocity,
+            &WaterState,
+            Has<Scuba>,
+            Has<Ragdoll>,
+            Has<ProxyCharacter>,
+            Has<CharacterControllerDisabled>,
+        ),
+        With<DebugOverlayTarget>,
+    >,
+    names: Query<NameOrEntity>,
+) {
+    for (mut text, mut node) in &mut texts {
+        let character = characters.iter().next();
+        node.display = if overlay.visible && character.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        let Some((state, velocity, water, scuba, ragdoll, proxy, disabled)) = character else {
+            continue;
+        };
+        if !overlay.visible {
+            continue;
+        }
+
+        let ground = state
+            .grounded
+            .and_then(|ground| names.get(ground.entity).ok())
+            .map(|name| name.to_string());
+        let abilities = [
+            (state.crouching, "crouching"),
+            (state.prone, "prone"),
+            (scuba, "scuba"),
+            (water.caught_in_current.is_some(), "caught in current"),
+            (ragdoll, "ragdoll"),
+            (proxy, "proxy"),
+            (disabled, "disabled"),
+        ]
+        .into_iter()
+        .filter_map(|(active, name)| active.then_some(name))
+        .collect::<Vec<_>>();
+        text.0 = format!(
+            "Speed: {:.3}\nHorizontal Speed: {:.3}\nVelocity: [{:.3}, {:.3}, {:.3}]\nGround: {}\nWater Level: {:?}\nActive: {}",
+            velocity.length(),
+            velocity.xz().length(),
+            velocity.x,
+            velocity.y,
+            velocity.z,
+            ground.as_deref().unwrap_or("none"),
+            water.level,
+            abilities.join(", "),
+        );
+    }
+}
@@ -16,28 +16,51 @@ impl Plugin for AhoyDynamicPlugin {
 }
 
 fn apply_forces(
-    kccs: Query<(&ComputedMass, &CharacterControllerOutput)>,
+    kccs: Query<
+        (
+            Entity,
+            &ComputedMass,
+            &CharacterController,
+            &CharacterControllerOutput,
+        ),
+        (
+            Without<ProxyCharacter>,
+            Without<Ragdoll>,
+            Without<CharacterControllerDisabled>,
+        ),
+    >,
     colliders: Query<&ColliderOf>,
-    mut rigid_bodies: Query<(&RigidBody, Forces)>,
+    mut rigid_bodies: Query<(&RigidBody, &ComputedMass, Forces)>,
 ) {
-    for (mass, output) in &kccs {
+    // Applying impulses in a different order yields slightly different results due to floating
+    // point rounding.
+    #[cfg(feature = "enhanced-determinism")]
+    let kccs = {
+        let mut kccs = kccs.iter().collect::<Vec<_>>();
+        kccs.sort_unstable_by_key(|(entity, ..)| *entity);
+        kccs
+    };
+    for (_entity, mass, cfg, output) in &kccs {
         let mass = mass.value();
         for touch in &output.touching_entities {
+            if touch.stepping_up {
+                continue;
+            }
             let Ok(collider_of) = colliders.get(touch.entity) else {
                 continue;
             };
-            let Ok((rigid_body, mut forces)) = rigid_bodies.get_mut(collider_of.body) else {
+            let Ok((rigid_body, body_mass, mut forces)) = rigid_bodies.get_mut(collider_of.body)
+            else {
                 continue;
             };
-            if !rigid_body.is_dynamic() {
+            if !rigid_body.is_dynamic() || body_mass.value() > cfg.max_push_mass {
                 continue;
             }
-            // TODO: not on step up
-
             let touch_dir = -touch.normal;
             let relative_velocity = touch.character_velocity - forces.linear_velocity();
             let touch_velocity = touch_dir.dot(relative_velocity) * touch_dir;
-            let impulse = touch_velocity * mass;
+            let impulse =
+                (touch_velocity * mass).clamp_length_max(body_mass.value() * cfg.max_push_speed);
 
             forces.apply_linear_impulse_at_point(impulse, touch.point);
         }
@@ -0,0 +1,89 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::f32::consts::PI;
+
+use crate::{CharacterLook, prelude::*};
+
+/// Turns characters with a [`BodyFacing`] after they moved.
+pub struct AhoyFacingPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyFacingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            turn_bodies.after(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// Turns the yaw of the character entity according to [`Self::mode`], e.g. for third-person
+/// setups where a body mesh is attached to the character.
+///
+/// The character moves relative to its [`CharacterLook`], so turning its body never changes where
+/// it walks. Characters without a [`CharacterLook`] are not turned.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct BodyFacing {
+    pub mode: FacingMode,
+    /// How fast the body turns, in radians per second.
+    pub turn_rate: f32,
+    /// Below this horizontal speed, the character counts as standing still, in meters per second.
+    pub min_speed: f32,
+}
+
+impl Default for BodyFacing {
+    fn default() -> Self {
+        Self {
+            mode: default(),
+            turn_rate: 4.0 * PI,
+            min_speed: 0.1,
+        }
+    }
+}
+
+/// Where a [`BodyFacing`] turns the character to.
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq, Eq)]
+pub enum FacingMode {
+    /// Face the direction the character moves in. Standing still keeps the current facing.
+    #[default]
+    Movement,
+    /// Always face where the camera looks.
+    Camera,
+    /// Face where the camera looks while moving, so that sideways input strafes. Standing still
+    /// keeps the current facing, so that the camera can orbit the character.
+    StrafeLock,
+}
+
+fn turn_bodies(
+    mut characters: Query<
+        (
+            &BodyFacing,
+            &CharacterLook,
+            &LinearVelocity,
+            &mut Transform,
+            &mut Rotation,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+    time: Res<Time>,
+) {
+    for (facing, look, velocity, mut transform, mut rotation) in &mut characters {
+        let moving = velocity.xz().length() >= facing.min_speed;
+        let target_yaw = match facing.mode {
+            FacingMode::Movement if moving => f32::atan2(-velocity.x, -velocity.z),
+            FacingMode::Camera => look.yaw,
+            FacingMode::StrafeLock if moving => look.yaw,
+            FacingMode::Movement | FacingMode::StrafeLock => continue,
+        };
+        let target = Quat::from_rotation_y(target_yaw);
+        let angle = transform.rotation.angle_between(target);
+        let max_angle = facing.turn_rate * time.delta_secs();
+        transform.rotation = if angle <= max_angle {
+            target
+        } else {
+            transform.rotation.slerp(target, max_angle / angle)
+        };
+        rotation.0 = transform.rotation;
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{CharacterControllerState, audio::surface_material, prelude::*};
+
+/// Triggers [`Footstep`]s for characters with [`Footsteps`].
+pub struct AhoyFootstepPlugin;
+
+impl Plugin for AhoyFootstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(trigger_footsteps);
+    }
+}
+
+/// Makes a character trigger a [`Footstep`] with every [`AnimationCueKind::Step`] it takes on the
+/// ground, e.g. for footstep sounds, dust puffs, or footprints.
+#[derive(Component, Clone, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Footsteps {
+    /// How far the character walks between two footsteps, in meters. Also spaces its
+    /// [`AnimationCueKind::Step`]s and footstep [`MovementSound`]s, so that they stay in sync.
+    pub stride: f32,
+    /// Below this horizontal speed, the character counts as standing still and makes no
+    /// footsteps, in meters per second.
+    pub min_speed: f32,
+    #[reflect(ignore)]
+    left: bool,
+}
+
+impl Default for Footsteps {
+    fn default() -> Self {
+        Self {
+            stride: 1.4,
+            min_speed: 0.5,
+            left: false,
+        }
+    }
+}
+
+/// Triggered when a character with [`Footsteps`] takes a step on the ground.
+#[derive(EntityEvent, Clone, Debug)]
+pub struct Footstep {
+    /// The character that took the step.
+    pub entity: Entity,
+    /// The collider the character stepped on.
+    pub ground: Entity,
+    /// Where the character touches the ground, expressed in world space.
+    pub point: Vec3,
+    /// The material of the ground, if it has a [`SurfaceMaterial`].
+    pub material: Option<SurfaceMaterial>,
+    /// Whether this is a step with the left foot. Alternates with every step.
+    pub left: bool,
+}
+
+fn trigger_footsteps(
+    cue: On<AnimationCue>,
+    mut characters: Query<(&mut Footsteps, &CharacterControllerState)>,
+    materials: Query<&SurfaceMaterial>,
+    colliders: Query<&ColliderOf>,
+    mut commands: Commands,
+) {
+    if cue.kind != AnimationCueKind::Step {
+        return;
+    }
+    let Ok((mut footsteps, state)) = characters.get_mut(cue.entity) else {
+        return;
+    };
+    let Some(ground) = state.grounded else {
+        return;
+    };
+    footsteps.left = !footsteps.left;
+    commands.trigger(Footstep {
+        entity: cue.entity,
+        ground: ground.entity,
+        point: ground.point1,
+        material: surface_material(ground.entity, &materials, &colliders),
+        left: footsteps.left,
+    });
+}
@@ -0,0 +1,67 @@
+use bevy_gltf::GltfExtras;
+use bevy_mesh::Mesh3d;
+use tracing::warn;
+
+use crate::prelude::*;
+
+/// Turns glTF nodes tagged in their extras into gameplay volumes, so that levels authored in e.g.
+/// Blender don't need TrenchBroom.
+///
+/// Tag a node with the custom property `ahoy` set to one of these values:
+///
+/// - `"water"`: Every mesh of the node becomes a [`Water`] volume.
+/// - `"interactable"`: The node becomes an [`Interactable`] with colliders for its meshes.
+pub struct AhoyGltfMarkupPlugin;
+
+impl Plugin for AhoyGltfMarkupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_gltf_markup);
+    }
+}
+
+/// The key in the glTF extras that holds the markup.
+const MARKUP_KEY: &str = "ahoy";
+
+fn apply_gltf_markup(
+    nodes: Query<(Entity, &GltfExtras), Added<GltfExtras>>,
+    children: Query<&Children>,
+    meshes: Query<(), With<Mesh3d>>,
+    mut commands: Commands,
+) {
+    for (node, extras) in &nodes {
+        let Ok(extras) = serde_json::from_str::<serde_json::Value>(&extras.value) else {
+            continue;
+        };
+        let Some(markup) = extras.get(MARKUP_KEY).and_then(|markup| markup.as_str()) else {
+            continue;
+        };
+        let node_meshes = core::iter::once(node)
+            .chain(children.iter_descendants(node))
+            .filter(|&entity| meshes.contains(entity));
+        match markup {
+            "water" => {
+                for mesh in node_meshes {
+                    commands.entity(mesh).insert((
+                        ColliderConstructor::ConvexHullFromMesh,
+                        Water {
+                            speed: 1.0,
+                            ..default()
+                        },
+                    ));
+                }
+            }
+            "interactable" => {
+                // Make the node the body of the colliders, so that hitting any of them uses it
+                commands
+                    .entity(node)
+                    .insert((Interactable::default(), RigidBody::Static));
+                for mesh in node_meshes {
+                    commands
+                        .entity(mesh)
+                        .insert(ColliderConstructor::ConvexHullFromMesh);
+                }
+            }
+            _ => warn!("Unknown {MARKUP_KEY} markup on glTF node: {markup}"),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use bevy_ui::prelude::*;
+
+use crate::{CharacterControllerState, prelude::*};
+
+/// Updates the [`Speedometer`], [`KeypressDisplay`], and [`StanceIndicator`] widgets.
+///
+/// The widgets are plain [`Text`]s, so style them with [`TextFont`], [`TextColor`], and [`Node`]
+/// like any other text.
+pub struct AhoyHudPlugin;
+
+impl Plugin for AhoyHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(press_movement_keys)
+            .add_observer(press_jump_key)
+            .add_observer(press_crouch_key)
+            .add_systems(
+                Update,
+                (
+                    update_speedometers,
+                    update_keypress_displays,
+                    update_stance_indicators,
+                ),
+            );
+    }
+}
+
+/// A text showing the speed of [`Self::character`].
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Text)]
+pub struct Speedometer {
+    pub character: Entity,
+    /// Only count the horizontal speed, like most surf and bunny hop HUDs do.
+    pub horizontal: bool,
+    /// How many decimals to show.
+    pub precision: usize,
+}
+
+impl Speedometer {
+    pub fn new(character: Entity) -> Self {
+        Self {
+            character,
+            horizontal: true,
+            precision: 0,
+        }
+    }
+}
+
+/// A text showing which movement keys [`Self::character`] is pressing, e.g. for showing inputs
+/// in recordings.
+///
+/// Unpressed keys are shown as `_`.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Text)]
+pub struct KeypressDisplay {
+    pub character: Entity,
+    movement: Vec2,
+    jump: bool,
+    crouch: bool,
+}
+
+impl KeypressDisplay {
+    pub fn new(character: Entity) -> Self {
+        Self {
+            character,
+            movement: Vec2::ZERO,
+            jump: false,
+            crouch: false,
+        }
+    }
+}
+
+/// A text showing whether [`Self::character`] is standing, crouching, prone, in the air, or
+/// swimming.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Text)]
+pub struct StanceIndicator {
+    pub character: Entity,
+}
+
+impl StanceIndicator {
+    pub fn new(character: Entity) -> Self {
+        Self { character }
+    }
+}
+
+fn press_movement_keys(movement: On<Fire<Movement>>, mut displays: Query<&mut KeypressDisplay>) {
+    for mut display in &mut displays {
+        if display.character == movement.context {
+            display.movement = movement.value;
+        }
+    }
+}
+
+fn press_jump_key(jump: On<Fire<Jump>>, mut displays: Query<&mut KeypressDisplay>) {
+    for mut display in &mut displays {
+        if display.character == jump.context {
+            display.jump = true;
+        }
+    }
+}
+
+fn press_crouch_key(crouch: On<Fire<Crouch>>, mut displays: Query<&mut KeypressDisplay>) {
+    for mut display in &mut displays {
+        if display.character == crouch.context {
+            display.crouch = true;
+        }
+    }
+}
+
+fn update_speedometers(
+    mut speedometers: Query<(&mut Text, &Speedometer)>,
+    characters: Query<&LinearVelocity>,
+) {
+    for (mut text, speedometer) in &mut speedometers {
+        let Ok(velocity) = characters.get(speedometer.character) else {
+            continue;
+        };
+        let speed = if speedometer.horizontal {
+            velocity.xz().length()
+        } else {
+            velocity.length()
+        };
+        text.0 = format!("{speed:.*}", speedometer.precision);
+    }
+}
+
+fn update_keypress_displays(mut displays: Query<(&mut Text, &mut KeypressDisplay)>) {
+    for (mut text, mut display) in &mut displays {
+        let key = |pressed: bool, name: &'static str| if pressed { name } else { "_" };
+        let movement = display.movement;
+        text.0 = [
+            key(movement.y > 0.0, "W"),
+            key(movement.x < 0.0, "A"),
+            key(movement.y < 0.0, "S"),
+            key(movement.x > 0.0, "D"),
+            key(display.jump, "Jump"),
+            key(display.crouch, "Crouch"),
+        ]
+        .join(" ");
+        // The actions only fire while the keys are held
+        display.movement = Vec2::ZERO;
+        display.jump = false;
+        display.crouch = false;
+    }
+}
+
+fn update_stance_indicators(
+    mut indicators: Query<(&mut Text, &StanceIndicator)>,
+    characters: Query<(&CharacterControllerState, &WaterState)>,
+) {
+    for (mut text, indicator) in &mut indicators {
+        let Ok((state, water)) = characters.get(indicator.character) else {
+            continue;
+        };
+        let stance = if water.level > WaterLevel::Feet {
+            "Swimming"
+        } else if state.grounded.is_none() {
+            "Airborne"
+        } else if state.prone {
+            "Prone"
+        } else if state.crouching {
+            "Crouching"
+        } else {
+            "Standing"
+        };
+        text.0 = stance.to_string();
+    }
+}
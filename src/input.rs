@@ -12,8 +12,12 @@ impl Plugin for AhoyInputPlugin {
     fn build(&self, app: &mut App) {
         app.add_observer(apply_movement)
             .add_observer(apply_jump)
+            .add_observer(release_jump)
             .add_observer(apply_global_movement)
             .add_observer(apply_crouch)
+            .add_observer(apply_sprint)
+            .add_observer(apply_prone)
+            .add_observer(apply_thrust)
             .add_observer(apply_swim_up)
             .add_systems(
                 RunFixedMainLoop,
@@ -45,27 +49,73 @@ pub struct SwimUp;
 #[action_output(bool)]
 pub struct Crouch;
 
+/// Lies down prone while held. Getting up again only works if there is enough room to crouch.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub struct Prone;
+
+/// Raises the ground speed to [`GroundConfig::sprint_speed`] while held.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub struct Sprint;
+
+/// Fires the [`Jetpack`] of the character while held.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub struct Thrust;
+
 #[derive(Debug, InputAction)]
 #[action_output(Vec2)]
 pub struct RotateCamera;
 
+/// Interacts with the [`Interactable`] the character is looking at.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub struct Use;
+
+/// Enters or leaves the `PhotoMode` of the character's camera.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub struct TogglePhotoMode;
+
+/// Rolls the camera while in `PhotoMode`. Positive values roll counterclockwise.
+#[derive(Debug, InputAction)]
+#[action_output(f32)]
+pub struct PhotoRoll;
+
+/// Zooms the camera while in `PhotoMode`. Positive values zoom in.
+#[derive(Debug, InputAction)]
+#[action_output(f32)]
+pub struct PhotoZoom;
+
 /// Input accumulated since the last fixed update loop. Is cleared after every fixed update loop.
 #[derive(Component, Clone, Reflect, Default, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct AccumulatedInput {
     // The last non-zero move that was input since the last fixed update loop
     pub last_movement: Option<Vec2>,
     // Time since the last jump input. Will be `None` once the jump was processed.
     pub jumped: Option<Stopwatch>,
+    // Whether the jump input is still held. Releasing it early cuts the jump short, see
+    // `JumpConfig::release_velocity_scale`.
+    pub jump_held: bool,
     // Whether any frame since the last fixed update loop input a swim up
     pub swim_up: bool,
     // Whether any frame since the last fixed update loop input a crouch
     pub crouched: bool,
+    // Whether any frame since the last fixed update loop input a sprint
+    pub sprinted: bool,
+    // Whether any frame since the last fixed update loop input going prone
+    pub proned: bool,
+    // Whether any frame since the last fixed update loop input a jetpack thrust
+    pub thrusted: bool,
 }
 
 fn apply_movement(
     movement: On<Fire<Movement>>,
-    mut accumulated_inputs: Query<&mut AccumulatedInput>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
 ) {
     if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(movement.context) {
         accumulated_inputs.last_movement = Some(movement.value);
@@ -74,7 +124,10 @@ fn apply_movement(
 
 fn apply_global_movement(
     movement: On<Fire<GlobalMovement>>,
-    mut query: Query<(&mut AccumulatedInput, &CharacterControllerState)>,
+    mut query: Query<
+        (&mut AccumulatedInput, &CharacterControllerState),
+        Without<CharacterControllerDisabled>,
+    >,
 ) {
     if let Ok((mut accumulated_inputs, state)) = query.get_mut(movement.context) {
         let global_move = movement.value;
@@ -86,31 +139,81 @@ fn apply_global_movement(
     }
 }
 
-fn apply_jump(jump: On<Fire<Jump>>, mut accumulated_inputs: Query<&mut AccumulatedInput>) {
+fn apply_jump(
+    jump: On<Fire<Jump>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
     if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(jump.context) {
         accumulated_inputs.jumped = Some(Stopwatch::new());
+        accumulated_inputs.jump_held = true;
     }
 }
 
-fn apply_swim_up(swim_up: On<Fire<SwimUp>>, mut accumulated_inputs: Query<&mut AccumulatedInput>) {
+fn release_jump(
+    jump: On<Complete<Jump>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
+    if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(jump.context) {
+        accumulated_inputs.jump_held = false;
+    }
+}
+
+fn apply_swim_up(
+    swim_up: On<Fire<SwimUp>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
     if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(swim_up.context) {
         accumulated_inputs.swim_up = true;
     }
 }
 
-fn apply_crouch(crouch: On<Fire<Crouch>>, mut accumulated_inputs: Query<&mut AccumulatedInput>) {
+fn apply_crouch(
+    crouch: On<Fire<Crouch>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
     if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(crouch.context) {
         accumulated_inputs.crouched = true;
     }
 }
 
+fn apply_sprint(
+    sprint: On<Fire<Sprint>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
+    if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(sprint.context) {
+        accumulated_inputs.sprinted = true;
+    }
+}
+
+fn apply_prone(
+    prone: On<Fire<Prone>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
+    if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(prone.context) {
+        accumulated_inputs.proned = true;
+    }
+}
+
+fn apply_thrust(
+    thrust: On<Fire<Thrust>>,
+    mut accumulated_inputs: Query<&mut AccumulatedInput, Without<CharacterControllerDisabled>>,
+) {
+    if let Ok(mut accumulated_inputs) = accumulated_inputs.get_mut(thrust.context) {
+        accumulated_inputs.thrusted = true;
+    }
+}
+
 fn clear_accumulated_input(mut accumulated_inputs: Query<&mut AccumulatedInput>) {
     for mut accumulated_input in &mut accumulated_inputs {
         *accumulated_input = AccumulatedInput {
             last_movement: default(),
             jumped: accumulated_input.jumped.clone(),
+            jump_held: accumulated_input.jump_held,
             swim_up: default(),
             crouched: default(),
+            sprinted: default(),
+            proned: default(),
+            thrusted: default(),
         }
     }
 }
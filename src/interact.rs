@@ -0,0 +1,109 @@
+use crate::{
+    CharacterControllerDerivedProps, CharacterControllerState, CharacterLook,
+    input::Use,
+    kcc::{forward, on_collider_or_body},
+    prelude::*,
+};
+
+/// Lets characters interact with [`Interactable`]s in front of them with the [`Use`] action.
+pub struct AhoyInteractPlugin;
+
+impl Plugin for AhoyInteractPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(interact);
+    }
+}
+
+/// Marks an entity that characters can use, e.g. a door or a button.
+///
+/// The entity must have a collider, or be the rigid body of one. When a character looks at it
+/// and fires the [`Use`] action, [`OnInteract`] is triggered for it. Sensors, e.g. [`Water`] or
+/// trigger zones, are looked through.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Interactable {
+    /// How far away from the character's eyes this can be used.
+    pub range: f32,
+}
+
+impl Default for Interactable {
+    fn default() -> Self {
+        Self { range: 2.0 }
+    }
+}
+
+/// Triggered for an [`Interactable`] when a character uses it.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct OnInteract {
+    /// The interactable that was used.
+    pub entity: Entity,
+    /// The character that used it.
+    pub character: Entity,
+    /// The point on the interactable the character looked at, expressed in world space.
+    pub point: Vec3,
+}
+
+/// No interactable can be used from further away than this.
+const MAX_INTERACT_RANGE: f32 = 100.0;
+
+fn interact(
+    use_action: On<Start<Use>>,
+    characters: Query<
+        (
+            &Transform,
+            &CharacterController,
+            &CharacterControllerState,
+            &CharacterControllerDerivedProps,
+            Option<&CharacterLook>,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+    interactables: Query<(Entity, &Interactable)>,
+    colliders: Query<&ColliderOf>,
+    sensors: Query<(), With<Sensor>>,
+    spatial: SpatialQuery,
+    mut commands: Commands,
+) {
+    let character = use_action.context;
+    let Ok((transform, cfg, state, derived, look)) = characters.get(character) else {
+        return;
+    };
+    // The eyes are at the same height the camera is synced to
+    let feet = derived
+        .standing_collider
+        .aabb(Vec3::default(), Rotation::default())
+        .min
+        .y;
+    let view_height = cfg.view_height(state);
+    let origin = transform.translation + Vec3::Y * (feet + view_height);
+    let direction = match look {
+        Some(look) => look.to_quat() * Vec3::NEG_Z,
+        None => forward(state.orientation),
+    };
+    let Ok(direction) = Dir3::new(direction) else {
+        return;
+    };
+
+    let Some(hit) = spatial.cast_ray_predicate(
+        origin,
+        direction,
+        MAX_INTERACT_RANGE,
+        true,
+        &cfg.filter,
+        &|entity| !sensors.contains(entity),
+    ) else {
+        return;
+    };
+    let Some((entity, interactable)) = on_collider_or_body(hit.entity, &interactables, &colliders)
+    else {
+        return;
+    };
+    if hit.distance > interactable.range {
+        return;
+    }
+    commands.trigger(OnInteract {
+        entity,
+        character,
+        point: origin + direction * hit.distance,
+    });
+}
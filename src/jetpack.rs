@@ -0,0 +1,110 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use bevy_time::Stopwatch;
+use core::time::Duration;
+
+use crate::{CharacterControllerState, prelude::*};
+
+/// Burns and refills the fuel of [`Jetpack`]s.
+pub struct AhoyJetpackPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyJetpackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_jetpacks.after(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// Pushes a character in the air upwards while the [`Thrust`] action is held, as long as there
+/// is fuel left.
+///
+/// Thrusting burns [`Self::burn_rate`] fuel per second and triggers [`JetpackFuelEmpty`] once the
+/// fuel runs out. The fuel refills after the character stopped thrusting for
+/// [`Self::regen_delay`].
+#[derive(Component, Clone, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Jetpack {
+    /// The upward acceleration while thrusting, in meters per second squared. Needs to be
+    /// higher than the gravity of the character to gain height.
+    pub thrust: f32,
+    pub fuel: f32,
+    pub max_fuel: f32,
+    /// How much fuel thrusting burns per second.
+    pub burn_rate: f32,
+    /// How much fuel is refilled per second while not thrusting.
+    pub regen_rate: f32,
+    /// How long the character has to stop thrusting before the fuel refills.
+    pub regen_delay: Duration,
+    /// Only refill the fuel while the character is on the ground.
+    pub regen_on_ground_only: bool,
+    #[reflect(ignore)]
+    since_thrust: Stopwatch,
+}
+
+impl Default for Jetpack {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl Jetpack {
+    pub fn new(max_fuel: f32) -> Self {
+        Self {
+            thrust: 30.0,
+            fuel: max_fuel,
+            max_fuel,
+            burn_rate: 40.0,
+            regen_rate: 25.0,
+            regen_delay: Duration::from_millis(500),
+            regen_on_ground_only: false,
+            since_thrust: Stopwatch::new(),
+        }
+    }
+
+    /// Whether there is any fuel left to thrust with.
+    pub fn has_fuel(&self) -> bool {
+        self.fuel > 0.0
+    }
+
+    /// The current fuel, from `0.0` for empty to `1.0` for full, e.g. for a fuel gauge.
+    pub fn fraction(&self) -> f32 {
+        (self.fuel / self.max_fuel.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+/// Triggered when a [`Jetpack`] runs out of fuel while thrusting.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct JetpackFuelEmpty {
+    pub entity: Entity,
+}
+
+fn update_jetpacks(
+    mut characters: Query<
+        (Entity, &mut Jetpack, &CharacterControllerState),
+        Without<CharacterControllerDisabled>,
+    >,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut jetpack, state) in &mut characters {
+        if state.thrusting {
+            let had_fuel = jetpack.has_fuel();
+            jetpack.fuel = (jetpack.fuel - jetpack.burn_rate * time.delta_secs()).max(0.0);
+            jetpack.since_thrust.reset();
+            if had_fuel && !jetpack.has_fuel() {
+                commands.trigger(JetpackFuelEmpty { entity });
+            }
+            continue;
+        }
+
+        jetpack.since_thrust.tick(time.delta());
+        let can_regen = !jetpack.regen_on_ground_only || state.grounded.is_some();
+        if can_regen && jetpack.since_thrust.elapsed() >= jetpack.regen_delay {
+            jetpack.fuel =
+                (jetpack.fuel + jetpack.regen_rate * time.delta_secs()).min(jetpack.max_fuel);
+        }
+    }
+}
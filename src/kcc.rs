@@ -1,12 +1,20 @@
 use avian3d::character_controller::move_and_slide::MoveHitData;
 use bevy_ecs::{
+    error::BevyError,
     intern::Interned,
-    query::QueryData,
+    query::{QueryData, QueryEntityError, QueryFilter, ROQueryItem, ReadOnlyQueryData},
     schedule::ScheduleLabel,
-    system::lifetimeless::{Read, Write},
+    system::{
+        SystemParam,
+        lifetimeless::{Read, Write},
+    },
 };
-use core::fmt::Debug;
-use tracing::warn;
+use core::{
+    f32::consts::{FRAC_PI_2, TAU},
+    fmt::Debug,
+    time::Duration,
+};
+use tracing::{debug, warn};
 
 use crate::{
     CharacterControllerDerivedProps, CharacterControllerOutput, CharacterControllerState,
@@ -19,14 +27,166 @@ pub struct AhoyKccPlugin {
 
 impl Plugin for AhoyKccPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(self.schedule, run_kcc.in_set(AhoySystems::MoveCharacters))
-            .add_systems(Update, spin_character_look);
+        app.add_systems(
+            self.schedule,
+            (
+                depenetrate_characters.in_set(MoveCharactersSystems::Depenetrate),
+                check_ground.in_set(MoveCharactersSystems::GroundCheck),
+                crouch_characters.in_set(MoveCharactersSystems::Crouch),
+                accelerate_characters.in_set(MoveCharactersSystems::Accelerate),
+                move_characters.in_set(MoveCharactersSystems::Move),
+                snap_characters.in_set(MoveCharactersSystems::Snap),
+                fly_characters.in_set(MoveCharactersSystems::Move),
+            ),
+        )
+        .add_systems(Update, spin_character_look);
     }
 }
 
+/// Triggered when the character's head hits a ceiling while moving up.
+///
+/// The upward velocity of the character is cancelled when this happens.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct HeadBump {
+    /// The character that bumped its head.
+    pub entity: Entity,
+    /// The entity of the ceiling collider that was hit.
+    pub ceiling: Entity,
+    /// The hit point on the ceiling, expressed in world space.
+    pub point: Vec3,
+    /// The outward surface normal of the ceiling at `point`, expressed in world space.
+    pub normal: Dir3,
+}
+
+/// Triggered when a character steps up onto or down from a stair step or a similar ledge, e.g. to
+/// smooth the camera or play stair sounds in proportion to the step.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct Stepped {
+    /// The character that stepped.
+    pub entity: Entity,
+    /// The entity of the collider that was stepped onto.
+    pub ground: Entity,
+    /// How far the character moved vertically. Positive when stepping up.
+    pub height: f32,
+}
+
+/// Triggered when a character starts standing on a moving platform, i.e. a rigid body that is
+/// not static, e.g. to start elevator logic.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct PlatformEntered {
+    /// The character standing on the platform.
+    pub entity: Entity,
+    /// The rigid body of the platform.
+    pub platform: Entity,
+    /// The velocity the character inherits from the platform.
+    pub base_velocity: Vec3,
+}
+
+/// Triggered when a character stops standing on a moving platform, e.g. because it jumped off.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct PlatformLeft {
+    /// The character that left the platform.
+    pub entity: Entity,
+    /// The rigid body of the platform.
+    pub platform: Entity,
+    /// The velocity the character inherited from the platform when leaving it.
+    pub base_velocity: Vec3,
+}
+
+/// Triggered when a character was stuck inside geometry for
+/// [`CharacterController::unstuck_ticks`] ticks in a row, trying to move without making progress
+/// as depenetration could not push it out.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct CharacterStuck {
+    /// The character that was stuck.
+    pub entity: Entity,
+    /// Where the character was stuck, expressed in world space.
+    pub position: Vec3,
+    /// The free position the character was moved to, or `None` if no free position was found
+    /// within [`CharacterController::unstuck_distance`].
+    pub resolved: Option<Vec3>,
+}
+
+/// Triggered when the KCC detected an invalid character state, e.g. after a physics blowup, and
+/// recovered from it.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct WatchdogRecovered {
+    /// The character that was recovered.
+    pub entity: Entity,
+    pub reason: WatchdogReason,
+    /// The translation the character had before recovering.
+    pub invalid_translation: Vec3,
+    /// The translation the character was restored to. This is the last valid translation if
+    /// there was one.
+    pub restored_translation: Vec3,
+}
+
+/// Why a [`WatchdogRecovered`] was triggered.
+#[derive(Clone, Copy, Reflect, Debug, PartialEq, Eq)]
+pub enum WatchdogReason {
+    /// The velocity was NaN or infinite. It was reset, but the transform was kept.
+    NonFiniteVelocity,
+    /// The [`Transform`] was NaN or infinite.
+    NonFiniteTransform,
+    /// The character was further away from the origin than [`CharacterController::max_position`].
+    OutOfBounds,
+}
+
+/// Triggered when a character jumps off a wall. See [`WallJumpConfig`].
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct WallJump {
+    /// The character that jumped.
+    pub entity: Entity,
+    /// The outward surface normal of the wall, expressed in world space.
+    pub normal: Vec3,
+}
+
+/// Triggered when a character touches the ground after being in the air, e.g. to spawn dust or
+/// splash particles where it landed.
+#[derive(EntityEvent, Clone, Debug)]
+pub struct LandingImpact {
+    /// The character that landed.
+    pub entity: Entity,
+    /// The entity of the ground collider that was landed on.
+    pub ground: Entity,
+    /// The contact point on the ground, expressed in world space.
+    pub point: Vec3,
+    /// The outward surface normal of the ground at `point`, expressed in world space.
+    pub normal: Vec3,
+    /// How fast the character moved into the ground, in meters per second.
+    pub speed: f32,
+    /// The material of the ground, if it or its rigid body has a [`SurfaceMaterial`].
+    pub material: Option<SurfaceMaterial>,
+}
+
+/// Triggered for every character right before it accelerates and moves, after its
+/// [`WishVelocity`] has been computed.
+///
+/// Observers can tweak the movement of this tick by mutating the character's [`WishVelocity`],
+/// [`LinearVelocity`], or [`CharacterControllerState`] through a query. This is a lighter-weight
+/// alternative to adding your own systems between the [`MoveCharactersSystems`].
+///
+/// Note that when using [`step_character`], this event is only observed after the step finished.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct BeforeCharacterMove {
+    /// The character that is about to move.
+    pub entity: Entity,
+}
+
+/// Triggered for every character after it moved and its velocity has been finalized.
+///
+/// Observers can inspect or adjust the resulting [`Transform`], [`LinearVelocity`], or
+/// [`CharacterControllerState`] through a query.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct AfterCharacterMove {
+    /// The character that moved.
+    pub entity: Entity,
+}
+
 #[derive(QueryData)]
 #[query_data(mutable, derive(Debug))]
 struct Ctx {
+    entity: Entity,
     velocity: Write<LinearVelocity>,
     state: Write<CharacterControllerState>,
     derived: Read<CharacterControllerDerivedProps>,
@@ -35,7 +195,25 @@ struct Ctx {
     input: Write<AccumulatedInput>,
     cfg: Read<CharacterController>,
     water: Read<WaterState>,
+    wish: Write<WishVelocity>,
+    impulse: Write<CharacterImpulse>,
+    scratch: Write<KccScratch>,
     look: Option<Read<CharacterLook>>,
+    scuba: Option<Read<Scuba>>,
+    stamina: Option<Read<Stamina>>,
+    jetpack: Option<Read<Jetpack>>,
+    layers: Option<Read<CollisionLayers>>,
+}
+
+/// Buffers reused across ticks, so that moving a character doesn't allocate.
+#[derive(Component, Debug, Default)]
+pub(crate) struct KccScratch {
+    /// The move-and-slide planes, including the ground plane.
+    planes: Vec<Dir3>,
+    /// The last translation and rotation that passed the watchdog.
+    last_good_transform: Option<(Vec3, Quat)>,
+    /// Whether the velocity had to be reset this tick because it was not finite.
+    invalid_velocity: bool,
 }
 
 #[derive(QueryData)]
@@ -53,100 +231,741 @@ struct ColliderComponents {
 #[derive(QueryData)]
 #[query_data(mutable, derive(Debug))]
 struct RigidBodyComponents {
+    rigid_body: Read<RigidBody>,
+    mass: Option<Read<ComputedMass>>,
     friction: Option<Read<Friction>>,
 }
 
-fn run_kcc(
-    mut kccs: Query<Ctx>,
-    time: Res<Time>,
-    move_and_slide: MoveAndSlide,
+/// The characters whose movement is simulated by the KCC.
+type Simulated = (
+    Without<ProxyCharacter>,
+    Without<Ragdoll>,
+    Without<CharacterControllerDisabled>,
+    Without<VaultProgress>,
+    Without<FlyMode>,
+);
+
+/// The characters in [`FlyMode`] that are moved by the KCC.
+type Flying = (Without<CharacterControllerDisabled>, Without<Ragdoll>);
+
+/// The colliders a character can stand on, be pushed by, or collide with.
+type Colliders<'w, 's> =
+    Query<'w, 's, ColliderComponents, (Without<CharacterController>, Without<Sensor>)>;
+
+/// The colliders a character can overlap without being stuck in them, e.g. [`Water`], trigger
+/// zones, and other characters.
+type NonSolid<'w, 's> = Query<'w, 's, (), Or<(With<Sensor>, With<CharacterController>)>>;
+
+/// Everything besides the character's own components that is needed to move it.
+#[derive(SystemParam)]
+struct KccParams<'w, 's> {
+    move_and_slide: MoveAndSlide<'w, 's>,
     // TODO: allow this to be other KCCs
-    colliders: Query<ColliderComponents, (Without<CharacterController>, Without<Sensor>)>,
-    rigid_bodies: Query<RigidBodyComponents>,
-    waters: Query<Entity, With<Water>>,
-    default_friction: Res<DefaultFriction>,
+    colliders: Colliders<'w, 's>,
+    non_solid: NonSolid<'w, 's>,
+    rigid_bodies: Query<'w, 's, RigidBodyComponents>,
+    waters: Query<'w, 's, &'static Water>,
+    materials: Query<'w, 's, &'static SurfaceMaterial>,
+    hit_rules: Query<'w, 's, &'static CharacterHitRule>,
+    speed_modifiers: Query<'w, 's, &'static SurfaceSpeedModifier>,
+    walkable_angles: WalkableAngles<'w, 's>,
+    slippery: Query<'w, 's, &'static Slippery>,
+    collider_bodies: Query<'w, 's, &'static ColliderOf>,
+    default_friction: Res<'w, DefaultFriction>,
+    physics_gravity: Res<'w, Gravity>,
+    commands: ParallelCommands<'w, 's>,
+}
+
+/// Looks up how steep the ground may be for characters to walk on it, per collider.
+#[derive(SystemParam)]
+pub struct WalkableAngles<'w, 's> {
+    overrides: Query<'w, 's, &'static WalkableAngleOverride>,
+    collider_bodies: Query<'w, 's, &'static ColliderOf>,
+}
+
+impl WalkableAngles<'_, '_> {
+    /// The [`GroundConfig::min_walk_cos`] for `entity`, respecting its [`WalkableAngleOverride`].
+    pub(crate) fn min_walk_cos(&self, entity: Entity, cfg: &CharacterController) -> f32 {
+        on_collider_or_body(entity, &self.overrides, &self.collider_bodies)
+            .map_or(cfg.ground.min_walk_cos, |walkable| walkable.min_walk_cos)
+    }
+
+    /// Whether the surface of `entity` with `normal` is a wall, i.e. too steep to walk on. Only
+    /// surfaces steeper than [`GroundConfig::min_walk_cos`] count, so that floors and ceilings
+    /// made unwalkable by a [`WalkableAngleOverride`] are no walls.
+    pub(crate) fn is_wall(&self, entity: Entity, normal: Vec3, cfg: &CharacterController) -> bool {
+        normal.y.abs() < self.min_walk_cos(entity, cfg).min(cfg.ground.min_walk_cos)
+    }
+}
+
+fn depenetrate_characters(mut kccs: Query<Ctx, Simulated>, time: Res<Time>, params: KccParams) {
+    for_each_character(&mut kccs, &time, |time, ctx| {
+        depenetrate_phase(time, &params, ctx);
+    });
+}
+
+// Proxy characters are moved by the network, but still detect the ground they are standing on.
+fn check_ground(
+    mut kccs: Query<
+        Ctx,
+        (
+            Without<Ragdoll>,
+            Without<CharacterControllerDisabled>,
+            Without<FlyMode>,
+        ),
+    >,
+    time: Res<Time>,
+    params: KccParams,
+) {
+    for_each_character(&mut kccs, &time, |time, ctx| {
+        ground_check_phase(time, &params, ctx);
+    });
+}
+
+fn crouch_characters(mut kccs: Query<Ctx, Simulated>, time: Res<Time>, params: KccParams) {
+    for_each_character(&mut kccs, &time, |time, ctx| {
+        crouch_phase(time, &params, ctx);
+    });
+}
+
+fn accelerate_characters(mut kccs: Query<Ctx, Simulated>, time: Res<Time>, params: KccParams) {
+    for_each_character(&mut kccs, &time, |time, ctx| {
+        accelerate_phase(time, &params, ctx);
+    });
+}
+
+fn move_characters(mut kccs: Query<Ctx, Simulated>, time: Res<Time>, params: KccParams) {
+    for_each_character(&mut kccs, &time, |time, ctx| {
+        move_phase(time, &params, ctx);
+    });
+}
+
+fn snap_characters(mut kccs: Query<Ctx, Simulated>, time: Res<Time>, params: KccParams) {
+    for_each_character(&mut kccs, &time, |time, ctx| {
+        snap_phase(time, &params, ctx);
+    });
+}
+
+fn fly_characters(mut kccs: Query<(Ctx, &FlyMode), Flying>, time: Res<Time>, params: KccParams) {
+    let step = |(mut ctx, fly): (CtxItem, &FlyMode)| {
+        let time = scaled_time(&time, ctx.cfg);
+        if !time.delta().is_zero() {
+            fly_phase(&time, fly, &params, &mut ctx);
+        }
+    };
+    #[cfg(feature = "enhanced-determinism")]
+    {
+        let mut kccs = kccs.iter_mut().collect::<Vec<_>>();
+        kccs.sort_unstable_by_key(|(ctx, _)| ctx.entity);
+        kccs.into_iter().for_each(step);
+    }
+    #[cfg(not(feature = "enhanced-determinism"))]
+    kccs.par_iter_mut().for_each(step);
+}
+
+/// Runs `phase` for every character with its own scaled time.
+///
+/// Characters are processed in parallel, unless `enhanced-determinism` is enabled, in which case
+/// they are processed one after another in a stable order.
+fn for_each_character<F: QueryFilter>(
+    kccs: &mut Query<Ctx, F>,
+    time: &Time,
+    phase: impl Fn(&Time, &mut CtxItem) + Send + Sync,
 ) {
-    let mut colliders = colliders.transmute_lens_inner();
-    let colliders = colliders.query();
-    let mut waters = waters.transmute_lens_inner();
-    let waters = waters.query();
-    for mut ctx in &mut kccs {
-        ctx.output.touching_entities.clear();
-        ctx.state.last_ground.tick(time.delta());
-        ctx.state.last_step_up.tick(time.delta());
-        ctx.state.last_step_down.tick(time.delta());
-
-        depenetrate_character(&move_and_slide, &mut ctx);
-        update_grounded(&move_and_slide, &colliders, &time, &mut ctx);
-
-        handle_crouching(&move_and_slide, &waters, &mut ctx);
-
-        if ctx.water.level <= WaterLevel::Feet {
-            // here we'd handle things like spectator, dead, noclip, etc.
-            start_gravity(&time, &mut ctx);
+    let step = |mut ctx: CtxItem| {
+        let time = scaled_time(time, ctx.cfg);
+        if time.delta().is_zero() {
+            return;
         }
+        phase(&time, &mut ctx);
+    };
+    #[cfg(feature = "enhanced-determinism")]
+    {
+        let mut kccs = kccs.iter_mut().collect::<Vec<_>>();
+        kccs.sort_unstable_by_key(|ctx| ctx.entity);
+        kccs.into_iter().for_each(step);
+    }
+    #[cfg(not(feature = "enhanced-determinism"))]
+    kccs.par_iter_mut().for_each(step);
+}
+
+/// Advances a single character by `delta`, outside of the regular schedule.
+///
+/// This runs the exact same movement code as [`AhoySystems::MoveCharacters`], using the
+/// character's current [`AccumulatedInput`]. Use this to simulate characters on a server, in
+/// tests, or when re-simulating predicted ticks. The results are written to the character's
+/// components, e.g. [`Transform`], [`LinearVelocity`], [`CharacterControllerState`], and
+/// [`CharacterControllerOutput`].
+///
+/// Characters that the regular schedule doesn't move, e.g. proxies, ragdolls, vaulting or
+/// disabled characters, are left untouched.
+///
+/// Note that systems you added between the [`MoveCharactersSystems`] are not run.
+pub fn step_character(world: &mut World, entity: Entity, delta: Duration) -> Result<(), BevyError> {
+    world.run_system_cached_with(step_single_character, (entity, delta))?
+}
+
+/// Runs the same collision queries the KCC uses to move a character, so that gameplay code moving
+/// characters on its own, e.g. grapples, knockbacks, or custom abilities, collides exactly like
+/// the KCC does.
+///
+/// The queries use the character's current collider, which depends on whether it is crouching,
+/// and its [`CharacterController`] settings. They only read the world, so applying their results
+/// is up to you.
+#[derive(SystemParam)]
+pub struct CharacterMotion<'w, 's> {
+    move_and_slide: MoveAndSlide<'w, 's>,
+    characters: Query<
+        'w,
+        's,
+        (
+            &'static CharacterController,
+            &'static CharacterControllerState,
+            &'static CharacterControllerDerivedProps,
+            &'static Transform,
+        ),
+    >,
+    walkable_angles: WalkableAngles<'w, 's>,
+}
+
+impl CharacterMotion<'_, '_> {
+    /// Sweeps the collider of `entity` along `movement` and returns the first hit, if any.
+    pub fn cast_move(
+        &self,
+        entity: Entity,
+        movement: Vec3,
+    ) -> Result<Option<MoveHitData>, QueryEntityError> {
+        let (cfg, state, derived, transform) = self.characters.get(entity)?;
+        Ok(cast_shape(
+            movement,
+            &self.move_and_slide,
+            derived.collider(state),
+            transform,
+            cfg,
+        ))
+    }
+
+    /// Like [`Self::cast_move`], but as if `entity` was at `translation`.
+    pub fn cast_move_from(
+        &self,
+        entity: Entity,
+        translation: Vec3,
+        movement: Vec3,
+    ) -> Result<Option<MoveHitData>, QueryEntityError> {
+        let (cfg, state, derived, transform) = self.characters.get(entity)?;
+        Ok(cast_shape(
+            movement,
+            &self.move_and_slide,
+            derived.collider(state),
+            &transform.with_translation(translation),
+            cfg,
+        ))
+    }
 
-        ctx.state.orientation = ctx
-            .look
-            .map(CharacterLook::to_quat)
-            .unwrap_or(ctx.transform.rotation);
-
-        let wish_velocity = calculate_wish_velocity(&ctx);
-        let wish_velocity_3d = calculate_3d_wish_velocity(&ctx);
-        handle_jump(wish_velocity, &time, &colliders, &move_and_slide, &mut ctx);
-
-        // Friction is handled before we add in any base velocity. That way, if we are on a conveyor,
-        //  we don't slow when standing still, relative to the conveyor.
-        friction(
-            &time,
-            &colliders,
-            &rigid_bodies,
-            &default_friction,
-            &mut ctx,
+    /// The offset that moves `entity` out of any colliders it intersects.
+    pub fn depenetrate(&self, entity: Entity) -> Result<Vec3, QueryEntityError> {
+        let (cfg, state, derived, transform) = self.characters.get(entity)?;
+        Ok(depenetration_offset(
+            &self.move_and_slide,
+            derived.collider(state),
+            transform,
+            cfg,
+        ))
+    }
+
+    /// The translation `entity` would be snapped down to when walking over walkable ground within
+    /// [`GroundConfig::step_size`] below it, or `None` if there is no such ground.
+    pub fn snap_to_ground(&self, entity: Entity) -> Result<Option<Vec3>, QueryEntityError> {
+        let (cfg, state, derived, transform) = self.characters.get(entity)?;
+        let snap = find_ground_snap(
+            &self.move_and_slide,
+            &self.walkable_angles,
+            derived.collider(state),
+            transform,
+            cfg,
+            0.0,
         );
+        Ok(snap.map(|(translation, _hit)| translation))
+    }
+
+    /// The normal of the closest wall within `distance` of `entity` horizontally, or `None` if
+    /// there is no wall nearby. Surfaces too steep to walk on count as walls.
+    pub fn closest_wall_normal(
+        &self,
+        entity: Entity,
+        distance: f32,
+    ) -> Result<Option<Dir3>, QueryEntityError> {
+        let (cfg, state, derived, transform) = self.characters.get(entity)?;
+        Ok(find_closest_wall(
+            distance,
+            &self.move_and_slide,
+            &self.walkable_angles,
+            derived.collider(state),
+            transform,
+            cfg,
+        ))
+    }
+
+    /// The [`GroundConfig::min_walk_cos`] of `entity` when walking on `surface`, respecting the
+    /// [`WalkableAngleOverride`] of `surface`.
+    pub fn min_walk_cos(&self, entity: Entity, surface: Entity) -> Result<f32, QueryEntityError> {
+        let (cfg, ..) = self.characters.get(entity)?;
+        Ok(self.walkable_angles.min_walk_cos(surface, cfg))
+    }
+
+    /// Whether `entity` treats the surface of `surface` with `normal` as a wall, i.e. as too steep
+    /// to walk on without being a floor or ceiling.
+    pub fn is_wall(
+        &self,
+        entity: Entity,
+        surface: Entity,
+        normal: Vec3,
+    ) -> Result<bool, QueryEntityError> {
+        let (cfg, ..) = self.characters.get(entity)?;
+        Ok(self.walkable_angles.is_wall(surface, normal, cfg))
+    }
+}
 
-        validate_velocity(&mut ctx);
+/// Sweeps the collider horizontally in a circle of directions and returns the normal of the
+/// closest surface too steep to walk on within `distance`.
+#[must_use]
+fn find_closest_wall(
+    distance: f32,
+    move_and_slide: &MoveAndSlide,
+    walkable_angles: &WalkableAngles,
+    collider: &Collider,
+    transform: &Transform,
+    cfg: &CharacterController,
+) -> Option<Dir3> {
+    const DIRECTIONS: u32 = 8;
+    if distance <= 0.0 {
+        return None;
+    }
+    (0..DIRECTIONS)
+        .filter_map(|i| {
+            let angle = TAU * i as f32 / DIRECTIONS as f32;
+            let movement = vec3(angle.cos(), 0.0, angle.sin()) * distance;
+            cast_shape(movement, move_and_slide, collider, transform, cfg)
+        })
+        .filter(|hit| walkable_angles.is_wall(hit.entity, hit.normal1, cfg))
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        .and_then(|hit| Dir3::new(hit.normal1).ok())
+}
+
+fn step_single_character(
+    In((entity, delta)): In<(Entity, Duration)>,
+    mut kccs: Query<(Ctx, Option<&FlyMode>)>,
+    simulated: Query<(), Simulated>,
+    flying: Query<(), Flying>,
+    params: KccParams,
+) -> Result<(), BevyError> {
+    let (mut ctx, fly) = kccs.get_mut(entity)?;
+    // Leave characters alone that the regular schedule skips, e.g. disabled or ragdolled ones
+    let skipped = match fly {
+        Some(_) => !flying.contains(entity),
+        None => !simulated.contains(entity),
+    };
+    if skipped {
+        return Ok(());
+    }
+    let mut time = Time::default();
+    time.advance_by(delta);
+    let time = scaled_time(&time, ctx.cfg);
+    if time.delta().is_zero() {
+        return Ok(());
+    }
+    if let Some(fly) = fly {
+        fly_phase(&time, fly, &params, &mut ctx);
+        return Ok(());
+    }
+    depenetrate_phase(&time, &params, &mut ctx);
+    ground_check_phase(&time, &params, &mut ctx);
+    crouch_phase(&time, &params, &mut ctx);
+    accelerate_phase(&time, &params, &mut ctx);
+    move_phase(&time, &params, &mut ctx);
+    snap_phase(&time, &params, &mut ctx);
+    Ok(())
+}
+
+fn depenetrate_phase(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    ctx.output.touching_entities.clear();
+    ctx.state.last_ground.tick(time.delta());
+    ctx.state.last_step_up.tick(time.delta());
+    ctx.state.last_step_down.tick(time.delta());
+    ctx.state.last_wall_touch.tick(time.delta());
+    ctx.state.last_wall_jump.tick(time.delta());
+
+    depenetrate_character(&params.move_and_slide, ctx);
+    handle_stuck(params, ctx);
+}
+
+fn ground_check_phase(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    update_grounded(params, time, ctx);
+}
+
+fn crouch_phase(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    handle_crouching(time, &params.move_and_slide, &params.non_solid, ctx);
+
+    ctx.state.orientation = ctx
+        .look
+        .map(CharacterLook::to_quat)
+        .unwrap_or(ctx.transform.rotation);
+
+    ctx.state.sprinting = ctx.input.sprinted
+        && !is_exhausted(ctx)
+        && !ctx.state.crouching
+        && ctx.state.grounded.is_some()
+        && ctx.water.level <= WaterLevel::Feet;
+    ctx.wish.planar = calculate_wish_velocity(surface_speed(params, ctx), ctx);
+    ctx.wish.spatial = calculate_3d_wish_velocity(ctx);
+    let entity = ctx.entity;
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(BeforeCharacterMove { entity });
+    });
+}
+
+fn accelerate_phase(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    let gravity = ctx.cfg.effective_gravity(&params.physics_gravity);
+    handle_water_exit_jump(
+        gravity,
+        &params.move_and_slide,
+        &params.walkable_angles,
+        &params.commands,
+        ctx,
+    );
+    if !is_swimming(ctx) {
+        // here we'd handle things like spectator, dead, noclip, etc.
+        start_gravity(gravity, time, ctx);
+    }
 
-        if ctx.water.level > WaterLevel::Feet {
-            water_move(wish_velocity_3d, &time, &move_and_slide, &mut ctx);
-        } else if ctx.state.grounded.is_some() {
-            ground_move(wish_velocity, &time, &move_and_slide, &mut ctx);
+    let wish_velocity = ctx.wish.planar;
+    handle_jump(
+        wish_velocity,
+        gravity,
+        time,
+        &params.colliders,
+        &params.move_and_slide,
+        &params.walkable_angles,
+        &params.commands,
+        ctx,
+    );
+    cut_jump(ctx);
+    apply_impulse(time, &params.colliders, ctx);
+
+    // Friction is handled before we add in any base velocity. That way, if we are on a conveyor,
+    //  we don't slow when standing still, relative to the conveyor.
+    let slippery = slippery(params, ctx).copied();
+    friction(
+        time,
+        &params.colliders,
+        &params.rigid_bodies,
+        &params.default_friction,
+        slippery,
+        ctx,
+    );
+
+    validate_velocity(ctx);
+
+    if is_swimming(ctx) {
+        swim(ctx.wish.spatial, time, &params.waters, ctx);
+    } else if ctx.state.grounded.is_some() {
+        ctx.velocity.y = 0.0;
+        let mut acceleration_hz = if ctx.state.sprinting {
+            ctx.cfg.ground.sprint_acceleration_hz
         } else {
-            air_move(wish_velocity, &time, &move_and_slide, &mut ctx);
+            ctx.cfg.ground.acceleration_hz
+        };
+        if let Some(slippery) = slippery {
+            acceleration_hz *= slippery.acceleration_scale;
+        }
+        ground_accelerate(wish_velocity, acceleration_hz, time, ctx);
+        ctx.velocity.y = 0.0;
+    } else {
+        let acceleration_hz = ctx.cfg.air.acceleration_hz;
+        match ctx.cfg.air.control {
+            AirControl::Strafe => air_accelerate(wish_velocity, acceleration_hz, time, ctx),
+            AirControl::Direct { deceleration_hz } => {
+                direct_air_accelerate(wish_velocity, acceleration_hz, deceleration_hz, time, ctx);
+            }
+            AirControl::None => {}
         }
+    }
+}
 
-        let _was_grounded = ctx.state.grounded.is_some();
-        update_grounded(&move_and_slide, &colliders, &time, &mut ctx);
-        validate_velocity(&mut ctx);
+fn move_phase(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    ctx.state.thrusting = false;
+    if is_swimming(ctx) {
+        water_move(time, params, ctx);
+    } else if ctx.state.grounded.is_some() {
+        ground_move(time, params, ctx);
+    } else {
+        air_move(time, params, ctx);
+    }
+}
 
-        if ctx.water.level <= WaterLevel::Feet {
-            finish_gravity(&time, &mut ctx);
-        }
+fn snap_phase(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    handle_ceiling_hit(params, ctx);
+    if !is_swimming(ctx) && ctx.state.grounded.is_some() {
+        snap_to_ground(time, params, ctx);
+    }
+
+    let was_grounded = ctx.state.grounded.is_some();
+    update_grounded(params, time, ctx);
+    validate_velocity(ctx);
+    if !was_grounded && let Some(ground) = ctx.state.grounded {
+        let impact = landing_impact(ground, params, ctx);
+        params.commands.command_scope(|mut commands| {
+            commands.trigger(AnimationCue {
+                entity: ctx.entity,
+                kind: AnimationCueKind::Land,
+            });
+            commands.trigger(impact);
+        });
+    }
+
+    if !is_swimming(ctx) {
+        let gravity = ctx.cfg.effective_gravity(&params.physics_gravity);
+        finish_gravity(gravity, time, ctx);
+    }
+
+    if ctx.state.water_jumping && (ctx.state.grounded.is_some() || ctx.velocity.y <= 0.0) {
+        ctx.state.water_jumping = false;
+    }
+    if ctx.state.grounded.is_some() {
+        ctx.velocity.y = ctx.state.platform_velocity.y;
+        ctx.state.last_ground.reset();
+        ctx.state.wall_jumps = 0;
+        ctx.state.air_jumps = 0;
+    } else {
+        remember_wall(params, ctx);
+    }
+    lock_idle_position(time, ctx);
+    update_platform(params, ctx);
+    run_watchdog(params, ctx);
+    // TODO: check_falling();
+    let entity = ctx.entity;
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(AfterCharacterMove { entity });
+    });
+}
+
+/// Moves a character in [`FlyMode`] in place of all other phases, ignoring collisions and gravity.
+fn fly_phase(time: &Time, fly: &FlyMode, params: &KccParams, ctx: &mut CtxItem) {
+    ctx.output.touching_entities.clear();
+    ctx.state.grounded = None;
+    ctx.state.platform = None;
+    ctx.state.platform_velocity = Vec3::ZERO;
+    ctx.state.platform_angular_velocity = Vec3::ZERO;
+    ctx.state.idle_anchor = None;
+    ctx.state.sprinting = false;
+    ctx.state.thrusting = false;
+    ctx.state.jump_rising = false;
+    ctx.state.orientation = ctx
+        .look
+        .map(CharacterLook::to_quat)
+        .unwrap_or(ctx.transform.rotation);
+    // Don't jump as soon as flying stops
+    ctx.input.jumped = None;
+
+    let entity = ctx.entity;
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(BeforeCharacterMove { entity });
+    });
+
+    let movement = ctx.input.last_movement.unwrap_or_default();
+    let mut wish_velocity =
+        movement.y * forward(ctx.state.orientation) + movement.x * right(ctx.state.orientation);
+    if ctx.input.swim_up || ctx.input.jump_held {
+        wish_velocity += Vec3::Y;
+    }
+    if ctx.input.crouched {
+        wish_velocity -= Vec3::Y;
+    }
+    let speed = if ctx.input.sprinted {
+        fly.speed * fly.sprint_speed_scale
+    } else {
+        fly.speed
+    };
+    let wish_velocity = wish_velocity.normalize_or_zero() * speed;
+    ctx.wish.planar = wish_velocity.with_y(0.0);
+    ctx.wish.spatial = wish_velocity;
+
+    let dt = time.delta_secs();
+    let max_change = if wish_velocity == Vec3::ZERO {
+        ctx.velocity.length() * (1.0 - (-fly.friction_hz * dt).exp())
+    } else {
+        speed * fly.acceleration_hz * dt
+    };
+    ctx.velocity.0 = ctx.velocity.move_towards(wish_velocity, max_change);
+    validate_velocity(ctx);
+    ctx.transform.translation += ctx.velocity.0 * dt;
 
-        if ctx.state.grounded.is_some() {
-            ctx.velocity.y = ctx.state.platform_velocity.y;
-            ctx.state.last_ground.reset();
+    run_watchdog(params, ctx);
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(AfterCharacterMove { entity });
+    });
+}
+
+fn update_platform(params: &KccParams, ctx: &mut CtxItem) {
+    let platform = ctx.state.grounded.and_then(|ground| {
+        let body = params.colliders.get(ground.entity).ok()?.body.body;
+        let rigid_body = params.rigid_bodies.get(body).ok()?.rigid_body;
+        (!rigid_body.is_static()).then_some(body)
+    });
+    let old_platform = ctx.state.platform;
+    if platform == old_platform {
+        return;
+    }
+    ctx.state.platform = platform;
+    let entity = ctx.entity;
+    let base_velocity = ctx.state.platform_velocity;
+    params.commands.command_scope(|mut commands| {
+        if let Some(platform) = old_platform {
+            commands.trigger(PlatformLeft {
+                entity,
+                platform,
+                base_velocity,
+            });
         }
-        // TODO: check_falling();
+        if let Some(platform) = platform {
+            commands.trigger(PlatformEntered {
+                entity,
+                platform,
+                base_velocity,
+            });
+        }
+    });
+}
+
+fn landing_impact(ground: MoveHitData, params: &KccParams, ctx: &CtxItem) -> LandingImpact {
+    // The velocity was already clipped against the ground while moving, so use the one it was hit with
+    let speed = ctx
+        .output
+        .touching_entities
+        .iter()
+        .filter(|touch| touch.normal.y >= min_walk_cos(touch.entity, params, ctx.cfg))
+        .map(|touch| -touch.character_velocity.dot(*touch.normal))
+        .fold((-ctx.velocity.y).max(0.0), f32::max);
+    let material =
+        on_collider_or_body(ground.entity, &params.materials, &params.collider_bodies).cloned();
+    LandingImpact {
+        entity: ctx.entity,
+        ground: ground.entity,
+        point: ground.point1,
+        normal: ground.normal1,
+        speed,
+        material,
     }
 }
 
-fn depenetrate_character(_move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
-    let offset = move_and_slide.depenetrate(
+fn depenetrate_character(move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
+    let offset = depenetration_offset(
+        move_and_slide,
         ctx.derived.collider(&ctx.state),
-        ctx.transform.translation,
-        ctx.transform.rotation,
-        &((&ctx.cfg.move_and_slide).into()),
-        &ctx.cfg.filter,
+        &ctx.transform,
+        ctx.cfg,
     );
     ctx.transform.translation += offset;
 }
 
-fn ground_move(_wish_velocity: Vec3, time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
-    ctx.velocity.y = 0.0;
-    ground_accelerate(wish_velocity, ctx.cfg.acceleration_hz, time, ctx);
-    ctx.velocity.y = 0.0;
+#[must_use]
+fn depenetration_offset(
+    move_and_slide: &MoveAndSlide,
+    collider: &Collider,
+    transform: &Transform,
+    cfg: &CharacterController,
+) -> Vec3 {
+    move_and_slide.depenetrate(
+        collider,
+        transform.translation,
+        transform.rotation,
+        &((&cfg.move_and_slide).into()),
+        &cfg.filter,
+    )
+}
+
+fn handle_stuck(params: &KccParams, ctx: &mut CtxItem) {
+    // Moving less than this while stuck counts as making no progress
+    const MIN_PROGRESS: f32 = 0.05;
+    if ctx.cfg.unstuck_ticks == 0 {
+        return;
+    }
+    let position = ctx.transform.translation;
+    let input_held = ctx
+        .input
+        .last_movement
+        .is_some_and(|movement| movement != Vec2::ZERO);
+    let made_progress = ctx
+        .state
+        .stuck_position
+        .is_some_and(|stuck_position| stuck_position.distance(position) >= MIN_PROGRESS);
+    if !input_held
+        || made_progress
+        || !is_intersecting(&params.move_and_slide, &params.non_solid, ctx)
+    {
+        ctx.state.stuck_ticks = 0;
+        ctx.state.stuck_position = None;
+        return;
+    }
+    ctx.state.stuck_position.get_or_insert(position);
+    ctx.state.stuck_ticks += 1;
+    if ctx.state.stuck_ticks < ctx.cfg.unstuck_ticks {
+        return;
+    }
+    ctx.state.stuck_ticks = 0;
+    ctx.state.stuck_position = None;
+    let resolved = find_free_position(params, ctx);
+    if let Some(resolved) = resolved {
+        ctx.transform.translation = resolved;
+        ctx.velocity.0 = Vec3::ZERO;
+    }
+    let stuck = CharacterStuck {
+        entity: ctx.entity,
+        position,
+        resolved,
+    };
+    debug!(
+        "Character {} was stuck at {position}, moved it to {resolved:?}",
+        ctx.entity
+    );
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(stuck);
+    });
+}
 
+/// Searches for the closest position the character fits in, in growing shells around it.
+#[must_use]
+fn find_free_position(params: &KccParams, ctx: &mut CtxItem) -> Option<Vec3> {
+    const SHELLS: u32 = 8;
+    // Prefer moving up, as characters usually get stuck by sinking into the ground
+    const DIRECTIONS: [Vec3; 10] = [
+        Vec3::Y,
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Z,
+        Vec3::NEG_Z,
+        vec3(1.0, 0.0, 1.0),
+        vec3(1.0, 0.0, -1.0),
+        vec3(-1.0, 0.0, 1.0),
+        vec3(-1.0, 0.0, -1.0),
+        Vec3::NEG_Y,
+    ];
+    let original_position = ctx.transform.translation;
+    let max_distance = ctx.cfg.unstuck_distance;
+    let free = (1..=SHELLS)
+        .map(|shell| max_distance * shell as f32 / SHELLS as f32)
+        .flat_map(|distance| {
+            DIRECTIONS.map(|direction| original_position + direction.normalize() * distance)
+        })
+        .find(|&candidate| {
+            ctx.transform.translation = candidate;
+            !is_intersecting(&params.move_and_slide, &params.non_solid, ctx)
+        });
+    ctx.transform.translation = original_position;
+    free
+}
+
+fn ground_move(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
     ctx.velocity.0 += ctx.state.platform_velocity;
     let speed = ctx.velocity.length();
 
@@ -159,23 +978,21 @@ fn ground_move(_wish_velocity: Vec3, time: &Time, _move_and_slide: &MoveAndSlide
     let mut movement = ctx.velocity.0 * time.delta_secs();
     movement.y = 0.0;
 
-    let hit = cast_move(movement, move_and_slide, ctx);
+    let hit = cast_move(movement, &params.move_and_slide, ctx);
 
     if hit.is_none() {
         ctx.transform.translation += movement;
         ctx.velocity.0 -= ctx.state.platform_velocity;
-        depenetrate_character(move_and_slide, ctx);
-        snap_to_ground(move_and_slide, ctx);
+        depenetrate_character(&params.move_and_slide, ctx);
         return;
     };
 
-    step_move(time, move_and_slide, ctx);
+    step_move(time, params, ctx);
 
     ctx.velocity.0 -= ctx.state.platform_velocity;
-    snap_to_ground(move_and_slide, ctx);
 }
 
-fn ground_accelerate(_wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx: &mut CtxItem) {
+fn ground_accelerate(wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx: &mut CtxItem) {
     let Ok((wish_dir, wish_speed)) = Dir3::new_and_length(wish_velocity) else {
         return;
     };
@@ -192,16 +1009,31 @@ fn ground_accelerate(_wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ct
     ctx.velocity.0 += accel_speed * wish_dir;
 }
 
-fn air_move(_wish_velocity: Vec3, time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
-    air_accelerate(wish_velocity, ctx.cfg.air_acceleration_hz, time, ctx);
+fn air_move(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    thrust_jetpack(time, ctx);
+
     ctx.velocity.0 += ctx.state.platform_velocity;
 
-    step_move(time, move_and_slide, ctx);
+    step_move(time, params, ctx);
 
     ctx.velocity.0 -= ctx.state.platform_velocity;
 }
 
-fn air_accelerate(_wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx: &mut CtxItem) {
+/// Accelerates the character upwards while its [`Jetpack`] is thrusting.
+fn thrust_jetpack(time: &Time, ctx: &mut CtxItem) {
+    let Some(jetpack) = ctx.jetpack else {
+        return;
+    };
+    if !ctx.input.thrusted || !jetpack.has_fuel() {
+        return;
+    }
+    ctx.velocity.y += jetpack.thrust * time.delta_secs();
+    ctx.state.thrusting = true;
+    // A jetpack takes over from the jump, so releasing jump must not cut the ascent short
+    ctx.state.jump_rising = false;
+}
+
+fn air_accelerate(wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx: &mut CtxItem) {
     let Ok((wish_dir, wish_speed)) = Dir3::new_and_length(wish_velocity) else {
         return;
     };
@@ -220,32 +1052,111 @@ fn air_accelerate(_wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx:
     ctx.velocity.0 += accel_speed * wish_dir;
 }
 
-fn water_move(
-    mut _wish_velocity: Vec3,
+/// Moves the horizontal velocity towards `wish_velocity`, ignoring any momentum the character
+/// has, as is common in platformers.
+fn direct_air_accelerate(
+    wish_velocity: Vec3,
+    acceleration_hz: f32,
+    deceleration_hz: f32,
     time: &Time,
-    _move_and_slide: &MoveAndSlide,
     ctx: &mut CtxItem,
 ) {
-    if ctx.input.swim_up {
-        ctx.input.swim_up = false;
-        wish_velocity += Vec3::Y * ctx.cfg.speed;
+    let target = wish_velocity.xz();
+    let hz = if target == Vec2::ZERO {
+        deceleration_hz
+    } else {
+        acceleration_hz
     };
-    // Avoid Space + W + Look up to go faster than either alone
-    wish_velocity = wish_velocity.clamp_length_max(ctx.cfg.speed);
-    if wish_velocity == Vec3::ZERO {
-        wish_velocity -= Vec3::Y * ctx.cfg.water_gravity;
+    let max_change = ctx.cfg.speed * hz * time.delta_secs();
+    let horizontal = ctx.velocity.xz().move_towards(target, max_change);
+    ctx.velocity.x = horizontal.x;
+    ctx.velocity.z = horizontal.y;
+}
+
+/// Whether the character moves through water instead of walking or falling.
+fn is_swimming(ctx: &CtxItem) -> bool {
+    ctx.water.level > WaterLevel::Feet && !ctx.state.water_jumping
+}
+
+/// Whether the character's [`Stamina`] ran out, so that it can't start sprinting or swimming up.
+fn is_exhausted(ctx: &CtxItem) -> bool {
+    ctx.stamina.is_some_and(Stamina::is_depleted)
+}
+
+fn swim(mut wish_velocity: Vec3, time: &Time, waters: &Query<&Water>, ctx: &mut CtxItem) {
+    let mut acceleration_hz = ctx.cfg.water.acceleration_hz;
+    if ctx.water.caught_in_current.is_some() {
+        // The current is too strong to swim against, so it alone decides where we're going. It is
+        // applied as base velocity in `water_move`, so we just stop swimming and let friction
+        // settle us into it.
+        ctx.input.swim_up = false;
+        wish_velocity = Vec3::ZERO;
+    } else if let Some(scuba) = ctx.scuba.copied() {
+        ctx.input.swim_up = false;
+        let movement = ctx.input.last_movement.unwrap_or_default();
+        wish_velocity = forward(ctx.state.orientation) * scuba.thrust_speed
+            + right(ctx.state.orientation) * movement.x * scuba.strafe_speed;
+        wish_velocity = wish_velocity.clamp_length_max(scuba.max_speed) * ctx.water.speed;
+        acceleration_hz = scuba.acceleration_hz;
+    } else {
+        if ctx.input.swim_up {
+            ctx.input.swim_up = false;
+            if !is_exhausted(ctx) {
+                wish_velocity += Vec3::Y * ctx.cfg.speed * ctx.water.speed;
+            }
+        };
+        // Avoid Space + W + Look up to go faster than either alone
+        wish_velocity = wish_velocity.clamp_length_max(ctx.cfg.speed * ctx.water.speed);
+        if wish_velocity == Vec3::ZERO {
+            let buoyancy = ctx
+                .water
+                .water
+                .and_then(|water| waters.get(water).ok())
+                .map_or(0.0, |water| water.buoyancy);
+            if !float(buoyancy, time, ctx) {
+                wish_velocity += Vec3::Y * buoyancy;
+            }
+        };
+        wish_velocity *= ctx.cfg.water.slowdown;
+    }
+
+    water_accelerate(wish_velocity, acceleration_hz, time, ctx);
+}
+
+/// Pulls an idle swimmer towards [`WaterConfig::float_height`] like a damped spring, so that it
+/// bobs at the surface. Returns whether the swimmer floats at all.
+///
+/// The damping settles the vertical velocity at the water's `buoyancy` instead of zero, so that
+/// swimmers float higher in dense brine and lower in heavy liquids.
+fn float(buoyancy: f32, time: &Time, ctx: &mut CtxItem) -> bool {
+    let (Some(float_height), Some(surface)) = (ctx.cfg.water.float_height, ctx.water.surface)
+    else {
+        return false;
     };
-    wish_velocity *= ctx.cfg.water_slowdown;
+    let feet = ctx.transform.translation.y + ctx.derived.pos_to_feet_dist(&ctx.state);
+    let depth = surface - feet;
+    let dt = time.delta_secs();
+    ctx.velocity.y += (depth - float_height) * ctx.cfg.water.float_stiffness * dt;
+    ctx.velocity.y =
+        buoyancy + (ctx.velocity.y - buoyancy) * (-ctx.cfg.water.float_damping_hz * dt).exp();
+    // Deep down, the spring would pull hard enough to shoot us out of the water
+    let max_speed = ctx.cfg.speed * ctx.water.speed * ctx.cfg.water.slowdown;
+    ctx.velocity.y = ctx.velocity.y.clamp(-max_speed, max_speed);
+    true
+}
 
-    water_accelerate(wish_velocity, ctx.cfg.water_acceleration_hz, time, ctx);
-    ctx.velocity.0 += ctx.state.platform_velocity;
+fn water_move(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    // The current carries the character like a moving platform, without being part of its own
+    // velocity, so that swimming against it is as easy as swimming with it
+    let base_velocity = ctx.state.platform_velocity + ctx.water.current;
+    ctx.velocity.0 += base_velocity;
 
-    step_move(time, move_and_slide, ctx);
+    step_move(time, params, ctx);
 
-    ctx.velocity.0 -= ctx.state.platform_velocity;
+    ctx.velocity.0 -= base_velocity;
 }
 
-fn water_accelerate(_wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx: &mut CtxItem) {
+fn water_accelerate(wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx: &mut CtxItem) {
     let Ok((wish_dir, wish_speed)) = Dir3::new_and_length(wish_velocity) else {
         return;
     };
@@ -262,25 +1173,32 @@ fn water_accelerate(_wish_velocity: Vec3, acceleration_hz: f32, time: &Time, ctx
     ctx.velocity.0 += accel_speed * wish_dir;
 }
 
-fn step_move(time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
+fn step_move(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    let move_and_slide = &params.move_and_slide;
     let original_position = ctx.transform.translation;
     let original_velocity = ctx.velocity.0;
-    let original_touching_entities = ctx.output.touching_entities.clone();
+    // The touches of both paths are recorded one after another, and the ones of the path that
+    // is not taken are removed again at the end.
+    let original_touch_count = ctx.output.touching_entities.len();
 
     // Slide the direct path
-    move_character(time, move_and_slide, ctx);
+    move_character(time, params, ctx);
 
-    let down_touching_entities = ctx.output.touching_entities.clone();
+    let down_touch_count = ctx.output.touching_entities.len();
     let down_position = ctx.transform.translation;
     let down_velocity = ctx.velocity.0;
 
+    // Don't fly up steep stairs faster than the cooldown allows
+    if ctx.state.last_step_up.elapsed() < ctx.cfg.ground.step_up_cooldown {
+        return;
+    }
+
     ctx.transform.translation = original_position;
     ctx.velocity.0 = original_velocity;
-    ctx.output.touching_entities = original_touching_entities;
 
     // step up
     let cast_dir = Dir3::Y;
-    let cast_len = ctx.cfg.step_size;
+    let cast_len = ctx.cfg.ground.step_size;
 
     let hit = cast_move(cast_dir * cast_len, move_and_slide, ctx);
 
@@ -296,26 +1214,32 @@ fn step_move(time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
     if hit.is_some() {
         ctx.transform.translation = down_position;
         ctx.velocity.0 = down_velocity;
-        ctx.output.touching_entities = down_touching_entities;
+        ctx.output.touching_entities.truncate(down_touch_count);
         return;
     }
 
     // try to slide from upstairs
-    move_character(time, move_and_slide, ctx);
+    move_character(time, params, ctx);
 
     let cast_dir = Dir3::NEG_Y;
     let hit = cast_move(cast_dir * cast_len, move_and_slide, ctx);
 
-    // If we either fall or slide down, use the direct move-and-slide instead
-    if !hit.is_some_and(|h| h.normal1.y >= ctx.cfg.min_walk_cos) {
+    // If we either fall, slide down, or would stand on a light prop, use the direct move-and-slide
+    // instead
+    if !hit.is_some_and(|h| {
+        h.normal1.y >= min_walk_cos(h.entity, params, ctx.cfg)
+            && is_steppable(h.entity, params, ctx.cfg)
+            && is_ground(h.entity, params, ctx)
+    }) {
         ctx.transform.translation = down_position;
         ctx.velocity.0 = down_velocity;
-        ctx.output.touching_entities = down_touching_entities;
+        ctx.output.touching_entities.truncate(down_touch_count);
         return;
     };
     let hit = hit.unwrap();
     ctx.transform.translation += cast_dir * hit.distance;
     depenetrate_character(move_and_slide, ctx);
+    let ground = hit.entity;
 
     let vec_up_pos = ctx.transform.translation;
 
@@ -325,10 +1249,24 @@ fn step_move(time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
     if down_dist >= up_dist {
         ctx.transform.translation = down_position;
         ctx.velocity.0 = down_velocity;
-        ctx.output.touching_entities = down_touching_entities;
+        ctx.output.touching_entities.truncate(down_touch_count);
     } else {
         ctx.velocity.y = down_velocity.y;
         ctx.state.last_step_up.reset();
+        let stepped = Stepped {
+            entity: ctx.entity,
+            ground,
+            height: vec_up_pos.y - original_position.y,
+        };
+        params.commands.command_scope(|mut commands| {
+            commands.trigger(stepped);
+        });
+        ctx.output
+            .touching_entities
+            .drain(original_touch_count..down_touch_count);
+        for touch in &mut ctx.output.touching_entities[original_touch_count..] {
+            touch.stepping_up = true;
+        }
     }
 }
 
@@ -341,71 +1279,210 @@ fn step_move(time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
 
 
 
-fn move_character(time: &Time, _move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
-    let mut config = ctx.cfg.move_and_slide.clone();
-    if let Some(grounded) = ctx.state.grounded {
-        config.planes.push(Dir3::new_unchecked(grounded.normal1));
+fn move_character(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    // Add the ground plane to a reused buffer instead of cloning the whole config
+    let config = ctx.state.grounded.map(|grounded| {
+        let mut planes = core::mem::take(&mut ctx.scratch.planes);
+        planes.clear();
+        planes.extend_from_slice(&ctx.cfg.move_and_slide.planes);
+        planes.push(Dir3::new_unchecked(grounded.normal1));
+        MoveAndSlideConfig {
+            planes,
+            ..ctx.cfg.move_and_slide
+        }
+    });
+
+    let substeps = substep_count(time, ctx);
+    let delta = time.delta() / substeps;
+    let memberships = memberships(ctx);
+    for _ in 0..substeps {
+        let out = params.move_and_slide.move_and_slide(
+            ctx.derived.collider(&ctx.state),
+            ctx.transform.translation,
+            ctx.transform.rotation,
+            ctx.velocity.0,
+            delta,
+            config.as_ref().unwrap_or(&ctx.cfg.move_and_slide),
+            &ctx.cfg.filter,
+            |hit| {
+                if !is_obstacle(hit.entity, memberships, params) {
+                    return false;
+                }
+                if ctx.output.touching_entities.len() < ctx.cfg.max_touching_entities {
+                    ctx.output.touching_entities.push(hit.into());
+                }
+                true
+            },
+        );
+        let _lost_velocity = (ctx.velocity.0 - out.projected_velocity).length();
+        ctx.transform.translation = out.position;
+        ctx.velocity.0 = out.projected_velocity;
+    }
+    if let Some(config) = config {
+        ctx.scratch.planes = config.planes;
     }
+}
 
-    let out = move_and_slide.move_and_slide(
+/// How many substeps to split this tick's movement into to avoid tunneling through thin
+/// geometry at high speeds.
+#[must_use]
+fn substep_count(time: &Time, ctx: &CtxItem) -> u32 {
+    let max_substeps = ctx.cfg.max_substeps.max(1);
+    let Some(fraction) = ctx.cfg.substep_fraction else {
+        return 1;
+    };
+    let max_distance = ctx.derived.radius(&ctx.state) * fraction;
+    if max_distance <= 0.0 {
+        return max_substeps;
+    }
+    let distance = ctx.velocity.length() * time.delta_secs();
+    ((distance / max_distance).ceil() as u32).clamp(1, max_substeps)
+}
+
+fn snap_to_ground(time: &Time, params: &KccParams, ctx: &mut CtxItem) {
+    let Some((target, hit)) = find_ground_snap(
+        &params.move_and_slide,
+        &params.walkable_angles,
         ctx.derived.collider(&ctx.state),
-        ctx.transform.translation,
-        ctx.transform.rotation,
-        ctx.velocity.0,
-        time.delta(),
-        &config,
-        &ctx.cfg.filter,
-        |hit| {
-            ctx.output.touching_entities.push(hit.into());
-            true
-        },
-    );
-    let _lost_velocity = (ctx.velocity.0 - out.projected_velocity).length();
-    ctx.transform.translation = out.position;
-    ctx.velocity.0 = out.projected_velocity;
+        &ctx.transform,
+        ctx.cfg,
+        magnetism_distance(time, ctx),
+    ) else {
+        return;
+    };
+    if !is_ground(hit.entity, params, ctx) {
+        return;
+    }
+    let original_position = ctx.transform.translation;
+    ctx.transform.translation = target;
+    let height = ctx.transform.translation.y - original_position.y;
+    if -height > ctx.cfg.ground.step_down_detection_distance {
+        ctx.state.last_step_down.reset();
+        let stepped = Stepped {
+            entity: ctx.entity,
+            ground: hit.entity,
+            height,
+        };
+        params.commands.command_scope(|mut commands| {
+            commands.trigger(stepped);
+        });
+    }
+    depenetrate_character(&params.move_and_slide, ctx);
 }
 
-fn snap_to_ground(_move_and_slide: &MoveAndSlide, ctx: &mut CtxItem) {
+
+/// Finds walkable ground up to [`GroundConfig::step_size`] plus `extra_distance` below the
+/// character, returning the translation that puts the character onto it.
+#[must_use]
+fn find_ground_snap(
+    move_and_slide: &MoveAndSlide,
+    walkable_angles: &WalkableAngles,
+    collider: &Collider,
+    transform: &Transform,
+    cfg: &CharacterController,
+    extra_distance: f32,
+) -> Option<(Vec3, MoveHitData)> {
     let cast_dir = Vec3::Y;
-    let cast_len = ctx.cfg.ground_distance;
+    let cast_len = cfg.ground.distance;
 
-    let hit = cast_move(cast_dir * cast_len, move_and_slide, ctx);
+    let hit = cast_shape(
+        cast_dir * cast_len,
+        move_and_slide,
+        collider,
+        transform,
+        cfg,
+    );
     let up_dist = hit.map(|h| h.distance).unwrap_or(cast_len);
-    let start = ctx.transform.translation + cast_dir * up_dist;
+    let start = transform.with_translation(transform.translation + cast_dir * up_dist);
     let cast_dir = Vec3::NEG_Y;
-    let cast_len = up_dist + ctx.cfg.step_size;
+    let cast_len = up_dist + cfg.ground.step_size + extra_distance;
 
-    let orig_pos = ctx.transform.translation;
-
-    ctx.transform.translation = start;
-    let hit = cast_move(cast_dir * cast_len, move_and_slide, ctx);
-    ctx.transform.translation = orig_pos;
-
-    let Some(hit) = hit else {
-        return;
-    };
+    let hit = cast_shape(cast_dir * cast_len, move_and_slide, collider, &start, cfg)?;
     if hit.intersects()
-        || hit.normal1.y < ctx.cfg.min_walk_cos
-        || hit.distance <= ctx.cfg.ground_distance
+        || hit.normal1.y < walkable_angles.min_walk_cos(hit.entity, cfg)
+        || hit.distance <= cfg.ground.distance
     {
-        return;
+        return None;
     }
-    let original_position = ctx.transform.translation;
-    ctx.transform.translation = start + cast_dir * hit.distance;
-    if original_position.y - ctx.transform.translation.y > ctx.cfg.step_down_detection_distance {
-        ctx.state.last_step_down.reset();
+    Some((start.translation + cast_dir * hit.distance, hit))
+}
+
+/// How much further the ground is snapped to at the current speed, so that the character hugs
+/// convex curves like rounded hills instead of flying off them.
+fn magnetism_distance(time: &Time, ctx: &CtxItem) -> f32 {
+    let magnetism = ctx.cfg.ground.magnetism;
+    let Some(ground) = ctx.state.grounded.filter(|_| magnetism > 0.0) else {
+        return 0.0;
+    };
+    let distance = ctx.velocity.xz().length() * time.delta_secs();
+    // The ground falls away at least as fast as the slope we're on
+    let normal = ground.normal1;
+    let slope = normal.xz().length() / normal.y.max(f32::EPSILON);
+    distance * (slope + magnetism)
+}
+
+/// Whether the character may step up onto `entity`. Light dynamic rigid bodies are pushed instead.
+fn is_steppable(entity: Entity, params: &KccParams, cfg: &CharacterController) -> bool {
+    let Ok(collider) = params.colliders.get(entity) else {
+        return true;
+    };
+    let Ok(body) = params.rigid_bodies.get(collider.body.body) else {
+        return true;
+    };
+    if !body.rigid_body.is_dynamic() {
+        return true;
     }
-    depenetrate_character(move_and_slide, ctx);
+    let mass = body.mass.map(ComputedMass::value).unwrap_or_default();
+    cfg.step_onto_dynamic && mass >= cfg.min_step_mass
 }
 
+/// The [`CharacterHitRule`] of `entity` or its rigid body, if it applies to characters in
+/// `memberships`.
+fn hit_rule<'a>(
+    entity: Entity,
+    memberships: LayerMask,
+    params: &'a KccParams,
+) -> Option<&'a CharacterHitRule> {
+    on_collider_or_body(entity, &params.hit_rules, &params.collider_bodies)
+        .filter(|rule| rule.characters & memberships != LayerMask::NONE)
+}
 
-fn update_grounded(
-    _move_and_slide: &MoveAndSlide,
-    colliders: &Query<ColliderComponents>,
-    time: &Time,
-    ctx: &mut CtxItem,
-) {
-    if ctx.water.level > WaterLevel::Feet {
+/// Queries the collider `entity`, falling back to its rigid body.
+pub(crate) fn on_collider_or_body<'a, 's, D: ReadOnlyQueryData, F: QueryFilter>(
+    entity: Entity,
+    query: &'a Query<'_, 's, D, F>,
+    collider_bodies: &Query<&ColliderOf>,
+) -> Option<ROQueryItem<'a, 's, D>> {
+    let body = collider_bodies.get(entity).map(|collider| collider.body);
+    [Ok(entity), body]
+        .into_iter()
+        .flatten()
+        .find_map(|entity| query.get(entity).ok())
+}
+
+/// Whether the character may stand on `entity`.
+fn is_ground(entity: Entity, params: &KccParams, ctx: &CtxItem) -> bool {
+    hit_rule(entity, memberships(ctx), params).is_none_or(|rule| rule.ground)
+}
+
+/// Whether `entity` blocks characters in `memberships`.
+fn is_obstacle(entity: Entity, memberships: LayerMask, params: &KccParams) -> bool {
+    hit_rule(entity, memberships, params).is_none_or(|rule| rule.obstacle)
+}
+
+/// The [`GroundConfig::min_walk_cos`] for `entity`, respecting its [`WalkableAngleOverride`].
+fn min_walk_cos(entity: Entity, params: &KccParams, cfg: &CharacterController) -> f32 {
+    params.walkable_angles.min_walk_cos(entity, cfg)
+}
+
+fn memberships(ctx: &CtxItem) -> LayerMask {
+    ctx.layers.copied().unwrap_or_default().memberships
+}
+
+fn update_grounded(params: &KccParams, time: &Time, ctx: &mut CtxItem) {
+    let move_and_slide = &params.move_and_slide;
+    let colliders = &params.colliders;
+    if is_swimming(ctx) {
         set_grounded(None, colliders, time, ctx);
         return;
     }
@@ -413,10 +1490,10 @@ fn update_grounded(
 
     let y_vel = ctx.velocity.y;
     let moving_up = y_vel > 0.0;
-    let mut moving_up_rapidly = y_vel > ctx.cfg.unground_speed;
+    let mut moving_up_rapidly = y_vel > ctx.cfg.ground.unground_speed;
     if moving_up_rapidly && ctx.state.grounded.is_some() {
         let ground_entity_y_vel = ctx.state.platform_velocity.y;
-        moving_up_rapidly = (y_vel - ground_entity_y_vel) > ctx.cfg.unground_speed;
+        moving_up_rapidly = (y_vel - ground_entity_y_vel) > ctx.cfg.ground.unground_speed;
     }
 
     let is_on_ladder = false;
@@ -425,13 +1502,15 @@ fn update_grounded(
     } else {
         let cast_dir = Dir3::NEG_Y;
         let cast_dist = if ctx.state.platform_velocity.y < 0.0 {
-            ctx.cfg.ground_distance - ctx.state.platform_velocity.y * time.delta_secs()
+            ctx.cfg.ground.distance - ctx.state.platform_velocity.y * time.delta_secs()
         } else {
-            ctx.cfg.ground_distance
+            ctx.cfg.ground.distance
         };
-        let hit = cast_move(cast_dir * cast_dist, move_and_slide, ctx);
+        let hit = cast_move(cast_dir * cast_dist, move_and_slide, ctx)
+            .map(|hit| sample_ground_normal(hit, move_and_slide, &params.walkable_angles, ctx));
         if let Some(hit) = hit
-            && hit.normal1.y >= ctx.cfg.min_walk_cos
+            && hit.normal1.y >= min_walk_cos(hit.entity, params, ctx.cfg)
+            && is_ground(hit.entity, params, ctx)
         {
             set_grounded(hit, colliders, time, ctx);
         } else {
@@ -441,15 +1520,83 @@ fn update_grounded(
     // TODO: fire ground changed event
 }
 
+/// Combines the normal of `hit` with walkable ground sampled in a ring around the character
+/// according to [`GroundConfig::normal_policy`], so that standing on edges, grates, or stair lips
+/// yields a stable ground normal.
 #[must_use]
-fn cast_move(movement: Vec3, _move_and_slide: &MoveAndSlide, ctx: &CtxItem) -> Option<MoveHitData> {
-    move_and_slide.cast_move(
+fn sample_ground_normal(
+    mut hit: MoveHitData,
+    move_and_slide: &MoveAndSlide,
+    walkable_angles: &WalkableAngles,
+    ctx: &CtxItem,
+) -> MoveHitData {
+    let samples = ctx.cfg.ground.ring_samples;
+    if samples == 0 {
+        return hit;
+    }
+    let radius = ctx.derived.radius(&ctx.state) * ctx.cfg.ground.ring_radius_scale;
+    let feet = ctx.transform.translation + Vec3::Y * ctx.derived.pos_to_feet_dist(&ctx.state);
+    // Start the rays a bit above the feet to also find ground we're slightly sunk into
+    let ray_offset = ctx.cfg.ground.step_size * 0.5;
+    let mut average = hit.normal1;
+    let mut steepest = hit.normal1;
+    let mut flattest = hit.normal1;
+    for i in 0..samples {
+        let angle = TAU * i as f32 / samples as f32;
+        let origin = feet + vec3(angle.cos() * radius, ray_offset, angle.sin() * radius);
+        let Some(ray_hit) = move_and_slide.query_pipeline.cast_ray(
+            origin,
+            Dir3::NEG_Y,
+            ray_offset * 2.0,
+            true,
+            &ctx.cfg.filter,
+        ) else {
+            continue;
+        };
+        if ray_hit.normal.y >= walkable_angles.min_walk_cos(ray_hit.entity, ctx.cfg) {
+            average += ray_hit.normal;
+            if ray_hit.normal.y < steepest.y {
+                steepest = ray_hit.normal;
+            }
+            if ray_hit.normal.y > flattest.y {
+                flattest = ray_hit.normal;
+            }
+        }
+    }
+    hit.normal1 = match ctx.cfg.ground.normal_policy {
+        GroundNormalPolicy::Average => average.normalize_or(hit.normal1),
+        GroundNormalPolicy::Steepest => steepest,
+        GroundNormalPolicy::Flattest => flattest,
+    };
+    hit
+}
+
+#[must_use]
+fn cast_move(movement: Vec3, move_and_slide: &MoveAndSlide, ctx: &CtxItem) -> Option<MoveHitData> {
+    cast_shape(
+        movement,
+        move_and_slide,
         ctx.derived.collider(&ctx.state),
-        ctx.transform.translation,
-        ctx.transform.rotation,
+        &ctx.transform,
+        ctx.cfg,
+    )
+}
+
+#[must_use]
+fn cast_shape(
+    movement: Vec3,
+    move_and_slide: &MoveAndSlide,
+    collider: &Collider,
+    transform: &Transform,
+    cfg: &CharacterController,
+) -> Option<MoveHitData> {
+    move_and_slide.cast_move(
+        collider,
+        transform.translation,
+        transform.rotation,
         movement,
-        ctx.cfg.move_and_slide.skin_width,
-        &ctx.cfg.filter,
+        cfg.move_and_slide.skin_width,
+        &cfg.filter,
     )
 }
 
@@ -457,7 +1604,7 @@ fn cast_move(movement: Vec3, _move_and_slide: &MoveAndSlide, ctx: &CtxItem) -> O
 
 fn set_grounded(
     new_ground: impl Into<Option<MoveHitData>>,
-    colliders: &Query<ColliderComponents>,
+    colliders: &Colliders,
     time: &Time,
     ctx: &mut CtxItem,
 ) {
@@ -519,14 +1666,15 @@ fn calculate_platform_movement(
 
 fn friction(
     time: &Time,
-    colliders: &Query<ColliderComponents>,
+    colliders: &Colliders,
     rigid_bodies: &Query<RigidBodyComponents>,
     default_friction: &DefaultFriction,
+    slippery: Option<Slippery>,
     ctx: &mut CtxItem,
 ) {
     let speed = if ctx.state.grounded.is_some() {
         ctx.velocity.xz().length()
-    } else if ctx.water.level > WaterLevel::Feet {
+    } else if is_swimming(ctx) {
         ctx.velocity.length()
     } else {
         return;
@@ -554,9 +1702,20 @@ fn friction(
         Friction::default().dynamic_coefficient
     };
 
-    let friction = ctx.cfg.friction_hz * surface_friction;
-    let control = f32::max(speed, ctx.cfg.stop_speed);
-    drop += control * friction * time.delta_secs();
+    let friction = ctx.cfg.ground.friction_hz * surface_friction;
+    drop += if let Some(slippery) = slippery {
+        // Only ever lose a fraction of the speed on slippery ground, so that we never stop dead
+        speed * (1.0 - (-friction * slippery.friction_scale * time.delta_secs()).exp())
+    } else {
+        match ctx.cfg.ground.friction_model {
+            FrictionModel::Source => {
+                let control = f32::max(speed, ctx.cfg.ground.stop_speed);
+                control * friction * time.delta_secs()
+            }
+            FrictionModel::Exponential => speed * (1.0 - (-friction * time.delta_secs()).exp()),
+            FrictionModel::Linear => ctx.cfg.speed * friction * time.delta_secs(),
+        }
+    };
 
     let mut new_speed = (speed - drop).max(0.0);
     if new_speed != speed {
@@ -567,28 +1726,50 @@ fn friction(
 
 
 
+fn apply_impulse(time: &Time, colliders: &Colliders, ctx: &mut CtxItem) {
+    let impulse = core::mem::take(&mut ctx.impulse.0);
+    if impulse == Vec3::ZERO {
+        return;
+    }
+    if impulse.y > 0.0 && ctx.state.grounded.is_some() {
+        set_grounded(None, colliders, time, ctx);
+        // Don't allow a coyote jump right after being launched
+        ctx.state.last_ground.set_elapsed(ctx.cfg.jump.coyote_time);
+    }
+    ctx.velocity.0 += impulse;
+}
+
 fn handle_jump(
     _wish_velocity: Vec3,
+    gravity: f32,
     time: &Time,
-    colliders: &Query<ColliderComponents>,
-    _move_and_slide: &MoveAndSlide,
+    colliders: &Colliders,
+    move_and_slide: &MoveAndSlide,
+    walkable_angles: &WalkableAngles,
+    commands: &ParallelCommands,
     ctx: &mut CtxItem,
 ) {
     let Some(jump_time) = ctx.input.jumped.clone() else {
         return;
     };
-    if jump_time.elapsed() > ctx.cfg.jump_input_buffer {
+
+    // Only allow jumping when grounded or within coyote time
+    if ctx.state.grounded.is_none() && ctx.state.last_ground.elapsed() > ctx.cfg.jump.coyote_time {
+        if jump_time.elapsed() <= ctx.cfg.wall_jump.input_buffer {
+            handle_wall_jump(move_and_slide, walkable_angles, commands, ctx);
+        }
+        if ctx.input.jumped.is_some() && jump_time.elapsed() <= ctx.cfg.jump.input_buffer {
+            handle_air_jump(gravity, commands, ctx);
+        }
         return;
     }
-    
-    // Only allow jumping when grounded or within coyote time
-    if ctx.state.grounded.is_none() && ctx.state.last_ground.elapsed() > ctx.cfg.coyote_time {
+    if jump_time.elapsed() > ctx.cfg.jump.input_buffer {
         return;
     }
-    
+
     set_grounded(None, colliders, time, ctx);
     // set last_ground to coyote time to make it not jump again after jumping ungrounds us
-    ctx.state.last_ground.set_elapsed(ctx.cfg.coyote_time);
+    ctx.state.last_ground.set_elapsed(ctx.cfg.jump.coyote_time);
     let jumpdir = Vec3::Y;
     ctx.input.jumped = None;
 
@@ -600,21 +1781,252 @@ fn handle_jump(
     // v = g * sqrt(2.0 * 45 / g )
     // v^2 = g * g * 2.0 * 45 / g
     // v = sqrt( g * 2.0 * 45 )
-    let fl_mul = (2.0 * ctx.cfg.gravity * ctx.cfg.jump_height).sqrt();
+    let fl_mul = (2.0 * gravity * ctx.cfg.jump.height).sqrt();
     ctx.velocity.0 += jumpdir * ground_factor * fl_mul + Vec3::Y * ctx.state.platform_velocity.y;
+    ctx.state.jump_rising = true;
+
+    commands.command_scope(|mut commands| {
+        commands.trigger(AnimationCue {
+            entity: ctx.entity,
+            kind: AnimationCueKind::JumpStart,
+        });
+    });
+}
+
+/// Keeps characters standing still on walkable slopes in place, as gravity, depenetration, and
+/// snapping can otherwise make them creep down slowly.
+fn lock_idle_position(time: &Time, ctx: &mut CtxItem) {
+    let idle = ctx.cfg.ground.idle_lock
+        && ctx.state.grounded.is_some()
+        && ctx.state.platform_velocity == Vec3::ZERO
+        && ctx.wish.planar == Vec3::ZERO
+        && ctx.velocity.xz().length() < ctx.cfg.ground.idle_lock_speed;
+    if !idle {
+        ctx.state.idle_anchor = None;
+        return;
+    }
+    // Moving further than creeping would is a real push, e.g. by a wall closing in
+    let max_creep = ctx.cfg.ground.idle_lock_speed * time.delta_secs();
+    let Some(anchor) = ctx
+        .state
+        .idle_anchor
+        .filter(|anchor| anchor.xz().distance(ctx.transform.translation.xz()) <= max_creep)
+    else {
+        ctx.state.idle_anchor = Some(ctx.transform.translation);
+        return;
+    };
+    ctx.transform.translation.x = anchor.x;
+    ctx.transform.translation.z = anchor.z;
+    ctx.velocity.x = 0.0;
+    ctx.velocity.z = 0.0;
+}
 
-    // TODO: Trigger jump event
+fn remember_wall(params: &KccParams, ctx: &mut CtxItem) {
+    let Some(wall) = ctx.output.touching_entities.iter().find(|touch| {
+        params
+            .walkable_angles
+            .is_wall(touch.entity, *touch.normal, ctx.cfg)
+    }) else {
+        return;
+    };
+    ctx.state.wall_normal = Some(*wall.normal);
+    ctx.state.last_wall_touch.reset();
+}
+
+/// Cuts the current jump short once the jump input is released.
+fn cut_jump(ctx: &mut CtxItem) {
+    if !ctx.state.jump_rising {
+        return;
+    }
+    let vertical_speed = ctx.velocity.y - ctx.state.platform_velocity.y;
+    if vertical_speed <= 0.0 {
+        ctx.state.jump_rising = false;
+    } else if !ctx.input.jump_held {
+        ctx.state.jump_rising = false;
+        ctx.velocity.y =
+            ctx.state.platform_velocity.y + vertical_speed * ctx.cfg.jump.release_velocity_scale;
+    }
+}
+
+fn handle_air_jump(gravity: f32, commands: &ParallelCommands, ctx: &mut CtxItem) {
+    if ctx.state.air_jumps >= ctx.cfg.jump.air_jumps {
+        return;
+    }
+    ctx.input.jumped = None;
+    ctx.state.air_jumps += 1;
+    let height = ctx.cfg.jump.air_jump_height.unwrap_or(ctx.cfg.jump.height);
+    // Replace the vertical velocity instead of adding to it, so that an air jump while falling
+    // still gains the full height
+    ctx.velocity.y = (2.0 * gravity * height).sqrt();
+    ctx.state.jump_rising = true;
+
+    commands.command_scope(|mut commands| {
+        commands.trigger(AnimationCue {
+            entity: ctx.entity,
+            kind: AnimationCueKind::JumpStart,
+        });
+    });
+}
+
+fn handle_wall_jump(
+    move_and_slide: &MoveAndSlide,
+    walkable_angles: &WalkableAngles,
+    commands: &ParallelCommands,
+    ctx: &mut CtxItem,
+) {
+    let cfg = &ctx.cfg.wall_jump;
+    if !cfg.enabled
+        || ctx.state.last_wall_jump.elapsed() < cfg.cooldown
+        || ctx.state.wall_jumps >= cfg.max_chain
+    {
+        return;
+    }
+    let touched_wall = ctx
+        .state
+        .wall_normal
+        .filter(|_| ctx.state.last_wall_touch.elapsed() <= cfg.input_window);
+    let Some(normal) = touched_wall.or_else(|| {
+        find_closest_wall(
+            cfg.detection_distance,
+            move_and_slide,
+            walkable_angles,
+            ctx.derived.collider(&ctx.state),
+            &ctx.transform,
+            ctx.cfg,
+        )
+        .map(Vec3::from)
+    }) else {
+        return;
+    };
+    let away = normal.with_y(0.0).normalize_or_zero();
+    if away == Vec3::ZERO {
+        return;
+    }
+    ctx.input.jumped = None;
+    // Only keep the velocity along the wall
+    let into_wall = ctx.velocity.dot(away).min(0.0);
+    ctx.velocity.0 += away * (cfg.away_speed - into_wall);
+    ctx.velocity.y = cfg.up_speed;
+
+    ctx.state.wall_normal = None;
+    ctx.state.last_wall_jump.reset();
+    ctx.state.wall_jumps += 1;
+    let entity = ctx.entity;
+    commands.command_scope(|mut commands| {
+        commands.trigger(AnimationCue {
+            entity,
+            kind: AnimationCueKind::JumpStart,
+        });
+        commands.trigger(WallJump { entity, normal });
+    });
 }
 
-fn start_gravity(time: &Time, ctx: &mut CtxItem) {
-    ctx.velocity.y += (ctx.state.platform_velocity.y - ctx.cfg.gravity * 0.5) * time.delta_secs();
+/// Jumps out of water onto a ledge in front of the character, like the water jump in Source.
+///
+/// The character is launched just high enough to clear the ledge, and ignores the water until it
+/// lands or falls back down.
+fn handle_water_exit_jump(
+    gravity: f32,
+    move_and_slide: &MoveAndSlide,
+    walkable_angles: &WalkableAngles,
+    commands: &ParallelCommands,
+    ctx: &mut CtxItem,
+) {
+    let max_height = ctx.cfg.water.exit_jump_height;
+    if ctx.water.level != WaterLevel::Waist || max_height <= 0.0 || ctx.scuba.is_some() {
+        return;
+    }
+    let Some(jump_time) = ctx.input.jumped.as_ref() else {
+        return;
+    };
+    if jump_time.elapsed() > ctx.cfg.jump.input_buffer {
+        return;
+    }
+    let direction = if ctx.wish.planar != Vec3::ZERO {
+        ctx.wish.planar
+    } else {
+        forward(ctx.state.orientation).with_y(0.0)
+    };
+    let Ok(direction) = Dir3::new(direction) else {
+        return;
+    };
+
+    // Find the edge in front of us
+    let Some(wall) = cast_move(
+        direction * ctx.cfg.water.exit_jump_distance,
+        move_and_slide,
+        ctx,
+    ) else {
+        return;
+    };
+    if !walkable_angles.is_wall(wall.entity, wall.normal1, ctx.cfg) {
+        return;
+    }
+
+    // Find the top of the ledge
+    let feet = ctx.transform.translation.y + ctx.derived.pos_to_feet_dist(&ctx.state);
+    let origin = (wall.point1 + direction * 0.05).with_y(feet + max_height);
+    let Some(top) = move_and_slide.query_pipeline.cast_ray(
+        origin,
+        Dir3::NEG_Y,
+        max_height,
+        true,
+        &ctx.cfg.filter,
+    ) else {
+        return;
+    };
+    if top.normal.y < walkable_angles.min_walk_cos(top.entity, ctx.cfg) {
+        return;
+    }
+    let rise = max_height - top.distance + ctx.cfg.move_and_slide.skin_width;
+    if cast_move(Vec3::Y * rise, move_and_slide, ctx).is_some() {
+        return;
+    }
+
+    ctx.input.jumped = None;
+    ctx.state.water_jumping = true;
+    ctx.state.jump_rising = false;
+    // Drift onto the ledge even without input, so that just pressing jump gets us out
+    ctx.velocity.0 = direction * ctx.cfg.speed * 0.5 + Vec3::Y * (2.0 * gravity * rise).sqrt();
+
+    commands.command_scope(|mut commands| {
+        commands.trigger(AnimationCue {
+            entity: ctx.entity,
+            kind: AnimationCueKind::JumpStart,
+        });
+    });
+}
+
+fn handle_ceiling_hit(params: &KccParams, ctx: &mut CtxItem) {
+    let Some(ceiling) = ctx.output.touching_entities.iter().find(|touch| {
+        // Like walls, ceilings made unwalkable by an override stay ceilings
+        let min_walk_cos =
+            min_walk_cos(touch.entity, params, ctx.cfg).min(ctx.cfg.ground.min_walk_cos);
+        touch.character_velocity.y > 0.0 && touch.normal.y < -min_walk_cos
+    }) else {
+        return;
+    };
+    ctx.velocity.y = ctx.velocity.y.min(0.0);
+    let head_bump = HeadBump {
+        entity: ctx.entity,
+        ceiling: ceiling.entity,
+        point: ceiling.point,
+        normal: ceiling.normal,
+    };
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(head_bump);
+    });
+}
+
+fn start_gravity(gravity: f32, time: &Time, ctx: &mut CtxItem) {
+    ctx.velocity.y += (ctx.state.platform_velocity.y - gravity * 0.5) * time.delta_secs();
     ctx.state.platform_velocity.y = 0.0;
 
     validate_velocity(ctx);
 }
 
-fn finish_gravity(time: &Time, ctx: &mut CtxItem) {
-    ctx.velocity.y -= ctx.cfg.gravity * 0.5 * time.delta_secs();
+fn finish_gravity(gravity: f32, time: &Time, ctx: &mut CtxItem) {
+    ctx.velocity.y -= gravity * 0.5 * time.delta_secs();
     validate_velocity(ctx);
 }
 
@@ -626,13 +2038,85 @@ fn validate_velocity(ctx: &mut CtxItem) {
                 ctx.velocity[i]
             );
             ctx.velocity[i] = 0.0;
+            ctx.scratch.invalid_velocity = true;
         }
     }
     ctx.velocity.0 = ctx.velocity.clamp_length(0.0, ctx.cfg.max_speed);
 }
 
+/// Guards against physics blowups, e.g. from degenerate colliders, by restoring the last valid
+/// transform whenever the character ends up with a non-finite or far out of bounds position.
+fn run_watchdog(params: &KccParams, ctx: &mut CtxItem) {
+    let transform = *ctx.transform;
+    let reason = if !transform.is_finite() {
+        Some(WatchdogReason::NonFiniteTransform)
+    } else if transform.translation.length_squared() > ctx.cfg.max_position.powi(2) {
+        Some(WatchdogReason::OutOfBounds)
+    } else if core::mem::take(&mut ctx.scratch.invalid_velocity) {
+        Some(WatchdogReason::NonFiniteVelocity)
+    } else {
+        None
+    };
+    if !transform.rotation.is_normalized() && transform.rotation.is_finite() {
+        ctx.transform.rotation = transform.rotation.normalize();
+    }
+    let Some(reason) = reason else {
+        ctx.scratch.last_good_transform = Some((ctx.transform.translation, ctx.transform.rotation));
+        return;
+    };
+    if reason != WatchdogReason::NonFiniteVelocity {
+        let (translation, rotation) = ctx.scratch.last_good_transform.unwrap_or_else(|| {
+            let translation = if transform.translation.is_finite() {
+                transform.translation.clamp_length_max(ctx.cfg.max_position)
+            } else {
+                Vec3::ZERO
+            };
+            (translation, Quat::IDENTITY)
+        });
+        ctx.transform.translation = translation;
+        ctx.transform.rotation = rotation;
+        if !ctx.transform.scale.is_finite() {
+            ctx.transform.scale = Vec3::ONE;
+        }
+        ctx.velocity.0 = Vec3::ZERO;
+    }
+    let recovered = WatchdogRecovered {
+        entity: ctx.entity,
+        reason,
+        invalid_translation: transform.translation,
+        restored_translation: ctx.transform.translation,
+    };
+    warn!(
+        "Character {} has an invalid state ({reason:?}) at {}, restored it to {}",
+        ctx.entity, recovered.invalid_translation, recovered.restored_translation
+    );
+    params.commands.command_scope(|mut commands| {
+        commands.trigger(recovered);
+    });
+}
+
+/// The [`SurfaceSpeedModifier`] of the ground the character stands on.
+fn surface_speed(params: &KccParams, ctx: &CtxItem) -> f32 {
+    ctx.state
+        .grounded
+        .and_then(|ground| {
+            on_collider_or_body(
+                ground.entity,
+                &params.speed_modifiers,
+                &params.collider_bodies,
+            )
+        })
+        .map_or(1.0, |modifier| modifier.0)
+}
+
+/// The [`Slippery`] of the ground the character stands on, if any.
+fn slippery<'a>(params: &'a KccParams, ctx: &CtxItem) -> Option<&'a Slippery> {
+    let ground = ctx.state.grounded?;
+    on_collider_or_body(ground.entity, &params.slippery, &params.collider_bodies)
+}
+
 #[must_use]
-fn calculate_wish_velocity(ctx: &CtxItem) -> Vec3 {
+fn calculate_wish_velocity(surface_speed: f32, ctx: &CtxItem) -> Vec3 {
     let movement = ctx.input.last_movement.unwrap_or_default();
     let mut forward = forward(ctx.state.orientation);
     forward.y = 0.0;
@@ -645,45 +2129,121 @@ fn calculate_wish_velocity(ctx: &CtxItem) -> Vec3 {
     let wish_dir = wish_vel.normalize_or_zero();
 
     // clamp the speed lower if ducking
-    let speed = if ctx.state.crouching {
-        ctx.cfg.speed * ctx.cfg.crouch_speed_scale
+    let speed = if ctx.state.prone {
+        ctx.cfg.speed * ctx.cfg.prone.speed_scale
+    } else if ctx.state.crouching {
+        ctx.cfg.speed * ctx.cfg.crouch.speed_scale
+    } else if ctx.state.sprinting {
+        ctx.cfg.ground.sprint_speed
     } else {
         ctx.cfg.speed
     };
-    wish_dir * speed
+    wish_dir * speed * surface_speed
 }
 
 #[must_use]
 fn calculate_3d_wish_velocity(ctx: &CtxItem) -> Vec3 {
     let movement = ctx.input.last_movement.unwrap_or_default();
-    let forward = forward(ctx.state.orientation);
-    let right = right(ctx.state.orientation);
+    let (yaw, pitch, _) = ctx.state.orientation.to_euler(EulerRot::YXZ);
+    let pitch = gate_swim_pitch(pitch, ctx.cfg.water.swim_pitch_deadzone);
+    let orientation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+    let forward = forward(orientation);
+    let right = right(orientation);
 
     let wish_vel = movement.y * forward + movement.x * right;
     let wish_dir = wish_vel.normalize_or_zero();
 
     // clamp the speed lower if ducking
-    let speed = if ctx.state.crouching {
-        ctx.cfg.speed * ctx.cfg.crouch_speed_scale
+    let speed = if ctx.state.prone {
+        ctx.cfg.speed * ctx.cfg.prone.speed_scale
+    } else if ctx.state.crouching {
+        ctx.cfg.speed * ctx.cfg.crouch.speed_scale
     } else {
         ctx.cfg.speed
     };
-    wish_dir * speed
+    wish_dir * speed * ctx.water.speed
 }
 
-fn handle_crouching(_move_and_slide: &MoveAndSlide, waters: &Query<Entity>, ctx: &mut CtxItem) {
-    if ctx.input.crouched {
+/// Flattens shallow look pitches so that swimming forward doesn't immediately drift up or down.
+#[must_use]
+fn gate_swim_pitch(pitch: f32, deadzone: f32) -> f32 {
+    let max_pitch = FRAC_PI_2;
+    let deadzone = deadzone.clamp(0.0, max_pitch - f32::EPSILON);
+    let gated = (pitch.abs() - deadzone).max(0.0) / (max_pitch - deadzone) * max_pitch;
+    gated.copysign(pitch)
+}
+
+fn handle_crouching(
+    time: &Time,
+    move_and_slide: &MoveAndSlide,
+    non_solid: &NonSolid,
+    ctx: &mut CtxItem,
+) {
+    if ctx.input.proned {
+        ctx.state.prone = true;
+        ctx.state.crouch_amount = 1.0;
+    } else if ctx.state.prone {
+        // try to get up into a crouch
+        ctx.state.prone = false;
+        ctx.state.prone = is_intersecting(move_and_slide, non_solid, ctx);
+    }
+    if ctx.state.prone {
         ctx.state.crouching = true;
-    } else if ctx.state.crouching {
-        // try to stand up
-        ctx.state.crouching = false;
-        let is_intersecting = is_intersecting(move_and_slide, waters, ctx);
-        ctx.state.crouching = is_intersecting;
+        return;
     }
+
+    let transition = ctx.cfg.crouch.transition.as_secs_f32();
+    let max_step = if transition > 0.0 {
+        time.delta_secs() / transition
+    } else {
+        1.0
+    };
+    if ctx.input.crouched {
+        ctx.state.crouch_amount = (ctx.state.crouch_amount + max_step).min(1.0);
+    } else if ctx.state.crouch_amount > 0.0 {
+        ctx.state.crouch_amount = stand_up(max_step, move_and_slide, non_solid, ctx);
+    }
+    ctx.state.crouching = ctx.state.crouch_amount > 0.0;
+}
+
+/// Tries to rise by `max_step`, returning the new crouch amount.
+#[must_use]
+fn stand_up(
+    max_step: f32,
+    move_and_slide: &MoveAndSlide,
+    non_solid: &NonSolid,
+    ctx: &mut CtxItem,
+) -> f32 {
+    let crouch_amount = ctx.state.crouch_amount;
+    let fits = |amount: f32, ctx: &mut CtxItem| {
+        ctx.state.crouch_amount = amount;
+        let fits = !is_intersecting(move_and_slide, non_solid, ctx);
+        ctx.state.crouch_amount = crouch_amount;
+        fits
+    };
+    if !ctx.cfg.crouch.partial_stand && !fits(0.0, ctx) {
+        return crouch_amount;
+    }
+    let target = (crouch_amount - max_step).max(0.0);
+    if fits(target, ctx) {
+        return target;
+    }
+    if !ctx.cfg.crouch.partial_stand {
+        return crouch_amount;
+    }
+    // Settle for the tallest collider height on the way that fits
+    let levels = ctx.derived.transition_colliders.len() + 1;
+    (1..levels)
+        .rev()
+        .map(|level| 1.0 - level as f32 / levels as f32)
+        .filter(|&amount| amount > target && amount < crouch_amount)
+        .find(|&amount| fits(amount, ctx))
+        .unwrap_or(crouch_amount)
 }
 
+/// Whether the character overlaps any solid collider, see [`NonSolid`].
 #[must_use]
-fn is_intersecting(_move_and_slide: &MoveAndSlide, waters: &Query<Entity>, ctx: &CtxItem) -> bool {
+fn is_intersecting(move_and_slide: &MoveAndSlide, non_solid: &NonSolid, ctx: &CtxItem) -> bool {
     let mut intersecting = false;
     // No need to worry about skin width, depenetration will take care of it.
     // If we used skin width, we could not stand up if we are closer than skin width to the ground,
@@ -694,7 +2254,7 @@ fn is_intersecting(_move_and_slide: &MoveAndSlide, waters: &Query<Entity>, ctx:
         ctx.transform.rotation,
         &ctx.cfg.filter,
         |e| {
-            if waters.contains(e) {
+            if non_solid.contains(e) {
                 return true;
             }
             intersecting = true;
@@ -705,13 +2265,18 @@ fn is_intersecting(_move_and_slide: &MoveAndSlide, waters: &Query<Entity>, ctx:
 }
 
 pub(crate) fn spin_character_look(
-    mut kccs: Query<(&CharacterControllerState, &mut CharacterLook)>,
+    mut kccs: Query<(
+        &CharacterController,
+        &CharacterControllerState,
+        &mut CharacterLook,
+    )>,
     time: Res<Time>,
 ) {
-    for (state, mut look) in &mut kccs {
+    for (cfg, state, mut look) in &mut kccs {
         if state.grounded.is_none() {
             continue;
         }
+        let time = scaled_time(&time, cfg);
         // Note: we're doing this using Quats (instead of just adding to the yaw) to avoid dealing
         // wrap around of angles.
         *look = CharacterLook::from_quat(
@@ -721,6 +2286,14 @@ pub(crate) fn spin_character_look(
     }
 }
 
+/// Returns a copy of `time` whose delta is scaled by [`CharacterController::time_scale`].
+#[must_use]
+fn scaled_time(time: &Time, cfg: &CharacterController) -> Time {
+    let mut scaled = Time::default();
+    scaled.advance_by(time.delta().mul_f32(cfg.time_scale.max(0.0)));
+    scaled
+}
+
 /// Convenience for getting the forward vector corresponding to an orientation.
 #[must_use]
 pub(crate) fn forward(orientation: Quat) -> Vec3 {
@@ -0,0 +1,99 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+
+use crate::prelude::*;
+
+/// Detects characters that fell below the [`KillZ`].
+pub struct AhoyKillZPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyKillZPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            kill_fallen_characters
+                .run_if(resource_exists::<KillZ>)
+                .after(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// The height below which characters count as having fallen out of the world, e.g. through a
+/// hole in the map. Insert this resource to enable the check.
+///
+/// Characters below it trigger [`FellOutOfWorld`] every tick until they are moved back up. If
+/// [`Self::respawn`] is enabled, characters with a [`RespawnPoint`] are moved back to it instead.
+#[derive(Resource, Clone, Copy, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct KillZ {
+    pub height: f32,
+    /// Automatically moves characters that fell out of the world back to their [`RespawnPoint`].
+    pub respawn: bool,
+}
+
+impl Default for KillZ {
+    fn default() -> Self {
+        Self {
+            height: -1000.0,
+            respawn: true,
+        }
+    }
+}
+
+/// Where a character is moved back to when it fell below the [`KillZ`].
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct RespawnPoint(pub Transform);
+
+/// Triggered when a character fell below the [`KillZ`].
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct FellOutOfWorld {
+    /// The character that fell out of the world.
+    pub entity: Entity,
+    /// The position the character fell out at, before it was respawned.
+    pub position: Vec3,
+    /// Whether the character was moved back to its [`RespawnPoint`].
+    pub respawned: bool,
+}
+
+fn kill_fallen_characters(
+    mut characters: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Position,
+            &mut Rotation,
+            &mut LinearVelocity,
+            &mut CharacterControllerState,
+            Option<&RespawnPoint>,
+        ),
+        With<CharacterController>,
+    >,
+    kill_z: Res<KillZ>,
+    mut commands: Commands,
+) {
+    for (entity, mut transform, mut position, mut rotation, mut velocity, mut state, respawn) in
+        &mut characters
+    {
+        // NaN positions fail this comparison as well, so those characters are caught too
+        if transform.translation.y >= kill_z.height {
+            continue;
+        }
+        let fell_at = transform.translation;
+        let respawn = respawn.filter(|_| kill_z.respawn);
+        if let Some(respawn) = respawn {
+            *transform = respawn.0;
+            position.0 = respawn.0.translation;
+            rotation.0 = respawn.0.rotation;
+            velocity.0 = Vec3::ZERO;
+            state.grounded = None;
+            state.platform_velocity = Vec3::ZERO;
+            state.platform_angular_velocity = Vec3::ZERO;
+        }
+        commands.trigger(FellOutOfWorld {
+            entity,
+            position: fell_at,
+            respawned: respawn.is_some(),
+        });
+    }
+}
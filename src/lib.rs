@@ -15,100 +15,346 @@ pub mod prelude {
         bevy_utils::prelude::*,
     };
 
+    #[cfg(feature = "camera")]
+    pub use crate::camera::{
+        CharacterControllerCamera, CharacterControllerCameraOf, ViewmodelOf, Viewmodels,
+    };
+    #[cfg(feature = "debug")]
+    pub use crate::debug::{AhoyDebugOverlay, DebugOverlayTarget};
+    #[cfg(feature = "hud")]
+    pub use crate::hud::{KeypressDisplay, Speedometer, StanceIndicator};
+    #[cfg(feature = "camera")]
+    pub use crate::photo_mode::PhotoMode;
+    #[cfg(feature = "tnua-migration")]
+    pub use crate::tnua::TnuaParameters;
+    #[cfg(feature = "trenchbroom")]
+    pub use crate::trenchbroom::{
+        FuncTrain, FuncWater, InfoPlayerStart, InfoTeleportDestination, PathCorner, TriggerPush,
+        TriggerTeleport,
+    };
     pub use crate::{
-        AhoyPlugins, AhoySystems, CharacterController, CharacterControllerState,
-        camera::{CharacterControllerCamera, CharacterControllerCameraOf},
+        AhoyExtraPlugins, AhoyPlugins, AhoySystems, AirConfig, AirControl, CharacterController,
+        CharacterControllerDisabled, CharacterControllerState, CharacterHitRule, CharacterImpulse,
+        CrouchConfig, FlyMode, FrictionModel, GroundConfig, GroundNormalPolicy, JumpConfig,
+        MoveCharactersSystems, NoClimb, ProneConfig, Slippery, SurfaceSpeedModifier,
+        WalkableAngleOverride, WallJumpConfig, WaterConfig, WishVelocity,
+        animation::{AnimationCue, AnimationCueKind, CharacterAnimationState, SlopeTilt},
+        audio::{MovementSound, MovementSoundKind, MovementSounds, SurfaceMaterial},
+        body::{CharacterControllerBody, CharacterControllerBodyOf},
+        facing::{BodyFacing, FacingMode},
+        footstep::{Footstep, Footsteps},
         input::{
-            Crouch, GlobalMovement, Jump, Movement, RotateCamera, SwimUp,
+            Crouch, GlobalMovement, Jump, Movement, PhotoRoll, PhotoZoom, Prone, RotateCamera,
+            Sprint, SwimUp, Thrust, TogglePhotoMode, Use,
+        },
+        interact::{Interactable, OnInteract},
+        jetpack::{Jetpack, JetpackFuelEmpty},
+        kcc::{
+            AfterCharacterMove, BeforeCharacterMove, CharacterMotion, CharacterStuck, HeadBump,
+            LandingImpact, PlatformEntered, PlatformLeft, Stepped, WallJump, WatchdogReason,
+            WatchdogRecovered,
+        },
+        kill_z::{FellOutOfWorld, KillZ, RespawnPoint},
+        path_mover::{PathMode, PathMover, PathWaypoint},
+        prediction::PredictionHistory,
+        proxy::ProxyCharacter,
+        race::{CheckpointZone, EndZone, RunFinished, RunSplit, RunStarted, RunTimer, StartZone},
+        ragdoll::Ragdoll,
+        recoil::Recoil,
+        remote_input::{InputFrame, RemoteInput},
+        replay::{GhostFrame, GhostPlayback, GhostPlaybackFinished, GhostRecorder, GhostTrack},
+        snapshot::CharacterSnapshot,
+        stamina::{Stamina, StaminaDepleted, StaminaRecovered},
+        vault::{Vault, VaultProgress, VaultStarted},
+        water::{
+            CaughtInCurrent, ReleasedFromCurrent, Scuba, Water, WaterLevel, WaterLevelChanged,
+            WaterState, WaterSurface,
         },
-        water::{Water, WaterLevel, WaterState},
     };
 }
 
+#[cfg(feature = "camera")]
+pub use crate::camera::AhoyCameraPlugin;
+#[cfg(feature = "debug")]
+pub use crate::debug::AhoyDebugPlugin;
+#[cfg(feature = "gltf")]
+pub use crate::gltf_markup::AhoyGltfMarkupPlugin;
+#[cfg(feature = "hud")]
+pub use crate::hud::AhoyHudPlugin;
+#[cfg(feature = "camera")]
+pub use crate::photo_mode::AhoyPhotoModePlugin;
+#[cfg(feature = "trenchbroom")]
+pub use crate::trenchbroom::AhoyTrenchBroomPlugin;
 pub use crate::{
-    camera::AhoyCameraPlugin, dynamics::AhoyDynamicPlugin,
-    fixed_update_utils::AhoyFixedUpdateUtilsPlugin, input::AhoyInputPlugin, kcc::AhoyKccPlugin,
+    animation::AhoyAnimationPlugin,
+    audio::AhoyAudioPlugin,
+    body::AhoyBodyPlugin,
+    dynamics::AhoyDynamicPlugin,
+    facing::AhoyFacingPlugin,
+    fixed_update_utils::AhoyFixedUpdateUtilsPlugin,
+    footstep::AhoyFootstepPlugin,
+    input::AhoyInputPlugin,
+    interact::AhoyInteractPlugin,
+    jetpack::AhoyJetpackPlugin,
+    kcc::{AhoyKccPlugin, step_character},
+    kill_z::AhoyKillZPlugin,
+    path_mover::AhoyPathMoverPlugin,
+    prediction::{AhoyPredictionPlugin, reconcile_character},
+    proxy::AhoyProxyPlugin,
+    race::AhoyRacePlugin,
+    ragdoll::{enter_ragdoll, exit_ragdoll},
+    recoil::AhoyRecoilPlugin,
+    remote_input::AhoyRemoteInputPlugin,
+    replay::AhoyReplayPlugin,
+    stamina::AhoyStaminaPlugin,
+    vault::AhoyVaultPlugin,
     water::AhoyWaterPlugin,
 };
-use crate::{input::AccumulatedInput, prelude::*};
+use crate::{
+    input::AccumulatedInput,
+    kcc::{CharacterStuck, KccScratch, WatchdogRecovered},
+    prelude::*,
+};
 use avian3d::{
     character_controller::move_and_slide::MoveHitData,
     parry::shape::{Capsule, SharedShape},
 };
 use bevy_app::PluginGroupBuilder;
 use bevy_ecs::{
-    intern::Interned, lifecycle::HookContext, relationship::RelationshipSourceCollection as _,
-    schedule::ScheduleLabel, world::DeferredWorld,
+    error::BevyError, intern::Interned, lifecycle::HookContext,
+    relationship::RelationshipSourceCollection as _, schedule::ScheduleLabel, world::DeferredWorld,
 };
 use bevy_time::Stopwatch;
 use core::time::Duration;
 use std::sync::Arc;
 
+mod animation;
+mod audio;
+mod body;
+#[cfg(feature = "camera")]
 pub mod camera;
+#[cfg(feature = "debug")]
+mod debug;
 mod dynamics;
+mod facing;
 mod fixed_update_utils;
+mod footstep;
+#[cfg(feature = "gltf")]
+mod gltf_markup;
+#[cfg(feature = "hud")]
+mod hud;
 pub mod input;
+mod interact;
+mod jetpack;
 mod kcc;
+mod kill_z;
+mod path_mover;
+#[cfg(feature = "camera")]
+mod photo_mode;
+mod prediction;
+mod proxy;
+mod race;
+mod ragdoll;
+mod recoil;
+mod remote_input;
+mod replay;
+mod snapshot;
+mod stamina;
+#[cfg(feature = "tnua-migration")]
+mod tnua;
+#[cfg(feature = "trenchbroom")]
+mod trenchbroom;
+mod vault;
 mod water;
 
 /// Plugin group for Ahoy's internal plugins.
 ///
 /// It requires you to add [`PhysicsPlugins`] and [`EnhancedInputPlugin`] to work properly.
+/// Gameplay features on top of the movement live in [`AhoyExtraPlugins`].
 pub struct AhoyPlugins {
     schedule: Interned<dyn ScheduleLabel>,
+    dynamics_schedule: Interned<dyn ScheduleLabel>,
 }
 
 impl AhoyPlugins {
     /// Create a new plugin group in the given schedule. The default is [`FixedPostUpdate`].
+    ///
+    /// The forces on dynamic rigid bodies are applied in the same schedule, unless configured
+    /// otherwise with [`Self::with_dynamics_schedule`].
     pub fn new(schedule: impl ScheduleLabel) -> Self {
+        let schedule = schedule.intern();
         Self {
-            schedule: schedule.intern(),
+            schedule,
+            dynamics_schedule: schedule,
         }
     }
+
+    /// Applies the forces of characters on dynamic rigid bodies in a different schedule than the
+    /// one the characters move in, e.g. for projects with custom physics scheduling.
+    ///
+    /// [`AhoySystems::ApplyForcesToDynamicRigidBodies`] is then no longer ordered after
+    /// [`AhoySystems::MoveCharacters`], so make sure that `schedule` runs after the movement.
+    pub fn with_dynamics_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.dynamics_schedule = schedule.intern();
+        self
+    }
 }
 
 impl Default for AhoyPlugins {
     fn default() -> Self {
-        Self {
-            schedule: FixedPostUpdate.intern(),
-        }
+        Self::new(FixedPostUpdate)
     }
 }
 
 impl PluginGroup for AhoyPlugins {
     fn build(self) -> PluginGroupBuilder {
-        PluginGroupBuilder::start::<Self>()
+        let group = PluginGroupBuilder::start::<Self>();
+        #[cfg(feature = "camera")]
+        let group = group.add(AhoyCameraPlugin).add(AhoyPhotoModePlugin);
+        #[cfg(feature = "debug")]
+        let group = group.add(AhoyDebugPlugin);
+        #[cfg(feature = "gltf")]
+        let group = group.add(AhoyGltfMarkupPlugin);
+        #[cfg(feature = "hud")]
+        let group = group.add(AhoyHudPlugin);
+        #[cfg(feature = "trenchbroom")]
+        let group = group.add(AhoyTrenchBroomPlugin {
+            schedule: self.schedule,
+        });
+        group
             .add(AhoySchedulePlugin {
                 schedule: self.schedule,
+                dynamics_schedule: self.dynamics_schedule,
             })
-            .add(AhoyCameraPlugin)
             .add(AhoyInputPlugin)
             .add(AhoyKccPlugin {
                 schedule: self.schedule,
             })
-            .add(AhoyWaterPlugin)
+            .add(AhoyWaterPlugin {
+                schedule: self.schedule,
+            })
             .add(AhoyFixedUpdateUtilsPlugin)
             .add(AhoyDynamicPlugin {
+                schedule: self.dynamics_schedule,
+            })
+    }
+}
+
+/// Plugin group for Ahoy's optional gameplay plugins, like interaction, prediction, animation,
+/// audio, replays, races, stamina, vaulting, and jetpacks.
+///
+/// Add it next to [`AhoyPlugins`] with the same schedule. Like with any [`PluginGroup`], single
+/// plugins can be left out with [`PluginGroupBuilder::disable`].
+pub struct AhoyExtraPlugins {
+    schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl AhoyExtraPlugins {
+    /// Create a new plugin group in the given schedule. The default is [`FixedPostUpdate`].
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for AhoyExtraPlugins {
+    fn default() -> Self {
+        Self::new(FixedPostUpdate)
+    }
+}
+
+impl PluginGroup for AhoyExtraPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(AhoyInteractPlugin)
+            .add(AhoyPredictionPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyRemoteInputPlugin {
                 schedule: self.schedule,
             })
+            .add(AhoyProxyPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyAnimationPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyAudioPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyRecoilPlugin)
+            .add(AhoyPathMoverPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyKillZPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyReplayPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyRacePlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyFacingPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyBodyPlugin)
+            .add(AhoyStaminaPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyVaultPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyJetpackPlugin {
+                schedule: self.schedule,
+            })
+            .add(AhoyFootstepPlugin)
     }
 }
 
 /// Plugin to setup schedule for [`AhoySystems`].
 pub struct AhoySchedulePlugin {
     pub schedule: Interned<dyn ScheduleLabel>,
+    /// The schedule of [`AhoySystems::ApplyForcesToDynamicRigidBodies`]. Usually the same as
+    /// [`Self::schedule`].
+    pub dynamics_schedule: Interned<dyn ScheduleLabel>,
 }
 
 impl Plugin for AhoySchedulePlugin {
     fn build(&self, app: &mut App) {
+        if self.dynamics_schedule == self.schedule {
+            app.configure_sets(
+                self.schedule,
+                (
+                    AhoySystems::MoveCharacters,
+                    AhoySystems::ApplyForcesToDynamicRigidBodies,
+                )
+                    .chain()
+                    .before(PhysicsSystems::First),
+            );
+        } else {
+            app.configure_sets(
+                self.schedule,
+                AhoySystems::MoveCharacters.before(PhysicsSystems::First),
+            )
+            .configure_sets(
+                self.dynamics_schedule,
+                AhoySystems::ApplyForcesToDynamicRigidBodies,
+            );
+        }
         app.configure_sets(
             self.schedule,
             (
-                AhoySystems::MoveCharacters,
-                AhoySystems::ApplyForcesToDynamicRigidBodies,
+                MoveCharactersSystems::Depenetrate,
+                MoveCharactersSystems::GroundCheck,
+                MoveCharactersSystems::Crouch,
+                MoveCharactersSystems::Accelerate,
+                MoveCharactersSystems::Move,
+                MoveCharactersSystems::Snap,
             )
                 .chain()
-                .before(PhysicsSystems::First),
+                .in_set(AhoySystems::MoveCharacters),
         );
     }
 }
@@ -120,16 +366,46 @@ pub enum AhoySystems {
     ApplyForcesToDynamicRigidBodies,
 }
 
+/// The phases of [`AhoySystems::MoveCharacters`], in the order they run.
+///
+/// Add your own systems between these sets to hook into a specific part of the movement, e.g.
+/// to modify the [`WishVelocity`] after [`MoveCharactersSystems::Crouch`].
+#[derive(SystemSet, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum MoveCharactersSystems {
+    /// Pushes characters out of any geometry they are stuck in.
+    Depenetrate,
+    /// Detects the ground the characters are standing on.
+    GroundCheck,
+    /// Handles crouching and computes the [`WishVelocity`].
+    Crouch,
+    /// Applies gravity, jumping, friction, and acceleration to the velocity.
+    Accelerate,
+    /// Moves the characters through the world.
+    Move,
+    /// Snaps characters to the ground and finalizes their velocity.
+    Snap,
+}
+
+/// The configuration of a kinematic character controller.
+///
+/// The character's [`Collider`] does not need to be centered on its origin. For rigs whose origin
+/// is at the feet, offset the shape using [`Collider::compound`]; feet, view heights, and the
+/// crouching collider are all derived from the collider's bounds.
 #[derive(Component, Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 #[require(
     AccumulatedInput,
     CharacterControllerState,
     CharacterControllerDerivedProps,
     CharacterControllerOutput,
+    KccScratch,
     TranslationInterpolation,
     RigidBody = RigidBody::Kinematic,
     WaterState,
+    WishVelocity,
+    CharacterImpulse,
     CustomPositionIntegration,
     Transform,
     SpeculativeMargin::ZERO,
@@ -137,69 +413,585 @@ pub enum AhoySystems {
 )]
 #[component(on_add=CharacterController::on_add)]
 pub struct CharacterController {
-    pub crouch_height: f32,
     pub filter: SpatialQueryFilter,
     pub standing_view_height: f32,
-    pub crouch_view_height: f32,
-    pub ground_distance: f32,
-    pub step_down_detection_distance: f32,
-    pub min_walk_cos: f32,
-    pub stop_speed: f32,
-    pub friction_hz: f32,
-    pub acceleration_hz: f32,
-    pub air_acceleration_hz: f32,
-    pub water_acceleration_hz: f32,
-    pub water_slowdown: f32,
-    pub gravity: f32,
-    pub water_gravity: f32,
-    pub step_size: f32,
-    pub crouch_speed_scale: f32,
     pub speed: f32,
-    pub air_speed: f32,
+    pub gravity: f32,
+    /// Use the downward component of Avian's [`Gravity`] resource instead of [`Self::gravity`],
+    /// so that changing the global gravity at runtime affects this character as well.
+    pub use_physics_gravity: bool,
+    /// Configures the move-and-slide algorithm. Heavy scenes can lower its
+    /// `move_and_slide_iterations` and `max_planes` to trade accuracy for speed.
     pub move_and_slide: MoveAndSlideConfig,
+    /// The maximum number of [`TouchingEntity`]s recorded per tick. Further hits are still
+    /// collided with, but not recorded, so that a pathological corner can't blow up a frame.
+    pub max_touching_entities: usize,
+    /// Splits a tick's movement into substeps whenever the distance moved exceeds this fraction
+    /// of the collider's radius, so that thin brushes can't be skipped at high speeds.
+    /// `None` disables substepping.
+    pub substep_fraction: Option<f32>,
+    /// The maximum number of substeps a tick's movement is split into.
+    pub max_substeps: u32,
     pub max_speed: f32,
-    pub jump_height: f32,
-    pub unground_speed: f32,
-    pub coyote_time: Duration,
-    pub jump_input_buffer: Duration,
+    /// The largest distance from the origin a character can be at. Characters further away are
+    /// considered to be in an invalid state and are restored to their last valid position,
+    /// triggering [`WatchdogRecovered`].
+    pub max_position: f32,
+    /// Multiplier for how fast time passes for this character, e.g. `0.5` for slow motion.
+    /// Scales every delta time and timer used when moving the character.
+    pub time_scale: f32,
+    /// The maximum velocity change a single push can impart on a dynamic rigid body.
+    ///
+    /// The impulse applied to a pushed body is clamped to this times the body's mass, so that
+    /// clipping a light prop at high speeds doesn't launch it across the map.
+    pub max_push_speed: f32,
+    /// Dynamic rigid bodies heavier than this are treated as immovable obstacles: the character
+    /// slides along them without pushing them.
+    pub max_push_mass: f32,
+    /// Whether the character can step up onto dynamic rigid bodies, e.g. crates or pallets. No
+    /// forces are applied to bodies while stepping onto them.
+    pub step_onto_dynamic: bool,
+    /// Dynamic rigid bodies lighter than this can't be stepped onto, and are pushed instead.
+    pub min_step_mass: f32,
+    /// After how many ticks in a row of being stuck inside geometry the character is moved to a
+    /// free position nearby, triggering [`CharacterStuck`]. A character only counts as stuck
+    /// while it overlaps a solid collider and tries to move without making progress. Zero, the
+    /// default, disables this.
+    pub unstuck_ticks: u32,
+    /// How far away a free position is searched for when the character is stuck.
+    pub unstuck_distance: f32,
+    pub ground: GroundConfig,
+    pub air: AirConfig,
+    pub jump: JumpConfig,
+    pub wall_jump: WallJumpConfig,
+    pub water: WaterConfig,
+    pub crouch: CrouchConfig,
+    pub prone: ProneConfig,
 }
 
 impl Default for CharacterController {
     fn default() -> Self {
         Self {
-            crouch_height: 1.3,
             filter: SpatialQueryFilter::default(),
             standing_view_height: 1.7,
-            crouch_view_height: 1.2,
-            ground_distance: 0.05,
-            min_walk_cos: 40.0_f32.to_radians().cos(),
-            stop_speed: 2.54,
-            friction_hz: 12.0,
-            acceleration_hz: 8.0,
-            air_acceleration_hz: 12.0,
-            water_acceleration_hz: 12.0,
-            water_slowdown: 0.6,
-            gravity: 29.0,
-            water_gravity: 2.4,
-            step_size: 0.7,
-            crouch_speed_scale: 1.0 / 3.0,
             speed: 12.0,
-            air_speed: 1.5,
+            gravity: 29.0,
+            use_physics_gravity: false,
             move_and_slide: MoveAndSlideConfig {
                 skin_width: 0.015,
                 ..default()
             },
+            max_touching_entities: 32,
+            substep_fraction: None,
+            max_substeps: 4,
             max_speed: 100.0,
-            jump_height: 1.8,
-            unground_speed: 10.0,
+            max_position: 1.0e6,
+            time_scale: 1.0,
+            max_push_speed: 10.0,
+            max_push_mass: f32::INFINITY,
+            step_onto_dynamic: true,
+            min_step_mass: 10.0,
+            unstuck_ticks: 0,
+            unstuck_distance: 1.0,
+            ground: default(),
+            air: default(),
+            jump: default(),
+            wall_jump: default(),
+            water: default(),
+            crouch: default(),
+            prone: default(),
+        }
+    }
+}
+
+/// The configuration of a [`CharacterController`] for walking on the ground.
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroundConfig {
+    /// How far below the character the ground is still detected.
+    pub distance: f32,
+    /// The number of extra ground samples taken in a ring around the character. Their normals are
+    /// averaged with the center sample to stabilize the ground normal on edges, grates, and stair
+    /// lips. `0` disables ring sampling.
+    pub ring_samples: u32,
+    /// The radius of the ground sample ring, as a fraction of the collider's radius.
+    pub ring_radius_scale: f32,
+    /// How the normals of the ring samples are combined into the ground normal.
+    pub normal_policy: GroundNormalPolicy,
+    pub step_size: f32,
+    /// The minimum time between two steps up, limiting how fast the character can climb very
+    /// steep stairs. Zero disables the limit.
+    pub step_up_cooldown: Duration,
+    pub step_down_detection_distance: f32,
+    /// Extends how far below the character the ground is snapped to, relative to the distance
+    /// moved, so that fast characters hug convex curves like rounded hills or half-pipes instead
+    /// of flying off them. Zero disables it.
+    pub magnetism: f32,
+    pub min_walk_cos: f32,
+    /// How [`Self::friction_hz`] slows the character down.
+    pub friction_model: FrictionModel,
+    pub stop_speed: f32,
+    pub friction_hz: f32,
+    pub acceleration_hz: f32,
+    /// The ground speed while the [`Sprint`] action is held.
+    pub sprint_speed: f32,
+    /// The ground acceleration while the [`Sprint`] action is held. See [`Self::acceleration_hz`].
+    pub sprint_acceleration_hz: f32,
+    /// Moving up faster than this relative to the ground ungrounds the character.
+    pub unground_speed: f32,
+    /// Pins characters in place while they stand still on walkable ground, so that they don't
+    /// slowly creep down slopes. Disabled by default.
+    pub idle_lock: bool,
+    /// Below this horizontal speed, a character without movement input counts as standing still.
+    pub idle_lock_speed: f32,
+}
+
+impl Default for GroundConfig {
+    fn default() -> Self {
+        Self {
+            distance: 0.05,
+            ring_samples: 0,
+            ring_radius_scale: 0.8,
+            normal_policy: default(),
+            step_size: 0.7,
+            step_up_cooldown: Duration::ZERO,
             step_down_detection_distance: 0.2,
+            magnetism: 0.0,
+            min_walk_cos: 40.0_f32.to_radians().cos(),
+            friction_model: default(),
+            stop_speed: 2.54,
+            friction_hz: 12.0,
+            acceleration_hz: 8.0,
+            sprint_speed: 18.0,
+            sprint_acceleration_hz: 8.0,
+            unground_speed: 10.0,
+            idle_lock: false,
+            idle_lock_speed: 0.1,
+        }
+    }
+}
+
+/// How [`GroundConfig::ring_samples`] are combined into the ground normal.
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroundNormalPolicy {
+    /// Average all walkable normals. Smooths out seams and stair lips.
+    #[default]
+    Average,
+    /// Use the steepest walkable normal, e.g. to slide off ramp edges sooner.
+    Steepest,
+    /// Use the flattest walkable normal, e.g. to stand still on stair lips.
+    Flattest,
+}
+
+/// How friction slows a character down. See [`GroundConfig::friction_model`].
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrictionModel {
+    /// Source style friction: the character slows down proportionally to its speed, but at
+    /// least as fast as if it was moving at [`GroundConfig::stop_speed`], so that it comes to a
+    /// crisp stop.
+    #[default]
+    Source,
+    /// The speed decays exponentially, so that the character glides out smoothly.
+    Exponential,
+    /// The character slows down at a constant rate of [`GroundConfig::friction_hz`] times
+    /// [`CharacterController::speed`] per second, independent of how fast it is.
+    Linear,
+}
+
+/// The configuration of a [`CharacterController`] for moving through the air.
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AirConfig {
+    pub speed: f32,
+    pub acceleration_hz: f32,
+    /// How much the player can steer while in the air.
+    pub control: AirControl,
+}
+
+impl Default for AirConfig {
+    fn default() -> Self {
+        Self {
+            speed: 1.5,
+            acceleration_hz: 12.0,
+            control: default(),
+        }
+    }
+}
+
+/// How a character accelerates while in the air. See [`AirConfig::control`].
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AirControl {
+    /// Quake and Source style air control. Input barely changes the speed in the direction of
+    /// the input, but turning while strafing accelerates the character, which allows air
+    /// strafing and bunny hopping.
+    #[default]
+    Strafe,
+    /// Steer freely, as on the ground. The horizontal velocity moves towards the wish velocity
+    /// with [`AirConfig::acceleration_hz`].
+    Direct {
+        /// How quickly the character slows down while there is no input, relative to
+        /// [`CharacterController::speed`]. `0.0` keeps the momentum.
+        deceleration_hz: f32,
+    },
+    /// No air control at all. The character keeps the momentum it had when leaving the ground.
+    None,
+}
+
+/// The configuration of a [`CharacterController`] for jumping.
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct JumpConfig {
+    pub height: f32,
+    /// How many extra jumps the character can do in the air before it has to touch the ground
+    /// again, e.g. `1` for a double jump.
+    pub air_jumps: u32,
+    /// The height of jumps in the air. `None` uses [`Self::height`].
+    pub air_jump_height: Option<f32>,
+    /// Scales the upward velocity when the [`Jump`] action is released while still rising, so
+    /// that tapping jump jumps lower than holding it. `1.0` always jumps to the full height.
+    pub release_velocity_scale: f32,
+    /// How long after leaving the ground the character can still jump.
+    pub coyote_time: Duration,
+    /// How long a jump input is remembered before the character can jump.
+    pub input_buffer: Duration,
+}
+
+impl Default for JumpConfig {
+    fn default() -> Self {
+        Self {
+            height: 1.8,
+            air_jumps: 0,
+            air_jump_height: None,
+            release_velocity_scale: 1.0,
             coyote_time: Duration::from_millis(100),
-            jump_input_buffer: Duration::from_millis(150),
+            input_buffer: Duration::from_millis(150),
+        }
+    }
+}
+
+/// The configuration of a [`CharacterController`] for jumping off walls while in the air.
+///
+/// The character is pushed away from the wall and up, keeping its speed along the wall. Jumping
+/// off the ground or within [`JumpConfig::coyote_time`] always takes precedence.
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WallJumpConfig {
+    pub enabled: bool,
+    /// The vertical speed of a wall jump.
+    pub up_speed: f32,
+    /// The speed a wall jump pushes the character away from the wall with.
+    pub away_speed: f32,
+    /// How long after touching a wall the character can still jump off it.
+    pub input_window: Duration,
+    /// Walls this close to the character can be jumped off without touching them first. Zero
+    /// only allows jumping off walls the character touched.
+    pub detection_distance: f32,
+    /// How long a jump input is remembered while in the air before the character reaches a wall.
+    /// Independent of [`JumpConfig::input_buffer`].
+    pub input_buffer: Duration,
+    /// The minimum time between two wall jumps.
+    pub cooldown: Duration,
+    /// How many wall jumps can be chained before the character has to touch the ground again.
+    pub max_chain: u32,
+}
+
+impl Default for WallJumpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            up_speed: 8.0,
+            away_speed: 6.0,
+            input_window: Duration::from_millis(150),
+            detection_distance: 0.3,
+            input_buffer: Duration::from_millis(100),
+            cooldown: Duration::from_millis(250),
+            max_chain: 3,
+        }
+    }
+}
+
+/// The configuration of a [`CharacterController`] for swimming.
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaterConfig {
+    pub acceleration_hz: f32,
+    pub slowdown: f32,
+    /// The look pitch in radians below which swimming stays level. Above it, the pitch is
+    /// remapped so that looking straight up or down still swims straight up or down.
+    pub swim_pitch_deadzone: f32,
+    /// How far below the water surface the feet of an idle swimmer settle, so that it floats
+    /// instead of sinking. Any swimming input overrides this. The [`Water::buoyancy`] shifts
+    /// where the swimmer settles. `None` lets characters drift with the [`Water::buoyancy`]
+    /// instead.
+    pub float_height: Option<f32>,
+    /// How strongly a swimmer is pulled towards its [`Self::float_height`], in meters per second
+    /// squared per meter of depth. Lower values bob more slowly.
+    pub float_stiffness: f32,
+    /// How quickly the bobbing around the [`Self::float_height`] dies down.
+    pub float_damping_hz: f32,
+    /// The highest ledge, measured from the feet, that a character swimming at
+    /// [`WaterLevel::Waist`] can jump out onto, so that it can climb out of pools with tall
+    /// edges. `0.0` disables jumping out of water.
+    pub exit_jump_height: f32,
+    /// How far in front of the character ledges are detected for jumping out of water.
+    pub exit_jump_distance: f32,
+}
+
+impl Default for WaterConfig {
+    fn default() -> Self {
+        Self {
+            acceleration_hz: 12.0,
+            slowdown: 0.6,
+            swim_pitch_deadzone: 10.0_f32.to_radians(),
+            float_height: Some(1.4),
+            float_stiffness: 20.0,
+            float_damping_hz: 3.0,
+            exit_jump_height: 1.6,
+            exit_jump_distance: 0.4,
+        }
+    }
+}
+
+/// The configuration of a [`CharacterController`] for crouching.
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrouchConfig {
+    pub height: f32,
+    pub view_height: f32,
+    pub speed_scale: f32,
+    /// How long it takes to go from standing to fully crouched and back. The collider and the
+    /// camera grow and shrink gradually over this time, and standing up stops while the taller
+    /// collider would not fit. Zero switches between standing and crouching instantly.
+    pub transition: Duration,
+    /// How many collider heights between standing and crouching are used during the
+    /// [`Self::transition`]. The colliders are built ahead of time, so more steps cost memory,
+    /// but not performance.
+    pub transition_steps: u32,
+    /// When there is not enough room to stand up fully, stand up as far as possible instead of
+    /// staying fully crouched, e.g. for vents and crawlspaces of varying height. The heights
+    /// are stepped according to [`Self::transition_steps`].
+    pub partial_stand: bool,
+}
+
+impl Default for CrouchConfig {
+    fn default() -> Self {
+        Self {
+            height: 1.3,
+            view_height: 1.2,
+            speed_scale: 1.0 / 3.0,
+            transition: Duration::ZERO,
+            transition_steps: 4,
+            partial_stand: false,
+        }
+    }
+}
+
+/// The configuration of a [`CharacterController`] for lying prone. See [`Prone`].
+#[derive(Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProneConfig {
+    /// The height of the collider while lying prone.
+    pub height: f32,
+    pub view_height: f32,
+    pub speed_scale: f32,
+}
+
+impl Default for ProneConfig {
+    fn default() -> Self {
+        Self {
+            height: 0.6,
+            view_height: 0.4,
+            speed_scale: 0.15,
         }
     }
 }
 
 impl CharacterController {
+    /// Quake-style movement: fast, with strong air control and low friction. Distances are
+    /// converted from Quake units at 1 unit = 1 inch.
+    pub fn quake() -> Self {
+        Self {
+            speed: 8.13,
+            gravity: 20.32,
+            ground: GroundConfig {
+                step_size: 0.457,
+                stop_speed: 2.54,
+                friction_hz: 4.0,
+                acceleration_hz: 10.0,
+                ..default()
+            },
+            air: AirConfig {
+                speed: 0.76,
+                acceleration_hz: 10.0,
+                ..default()
+            },
+            jump: JumpConfig {
+                height: 1.16,
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    /// Half-Life 2 style movement: slower than Quake, with lower gravity and jumps. Distances
+    /// are converted from Source units at 1 unit = 1 inch.
+    pub fn source_hl2() -> Self {
+        Self {
+            speed: 4.83,
+            gravity: 15.24,
+            ground: GroundConfig {
+                step_size: 0.457,
+                stop_speed: 2.54,
+                friction_hz: 4.0,
+                acceleration_hz: 10.0,
+                ..default()
+            },
+            air: AirConfig {
+                speed: 0.76,
+                acceleration_hz: 10.0,
+                ..default()
+            },
+            jump: JumpConfig {
+                height: 0.53,
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    /// Snappy platformer movement: near-instant acceleration, full air control, high jumps, and
+    /// generous coyote time and jump buffering.
+    pub fn platformer() -> Self {
+        Self {
+            speed: 8.0,
+            gravity: 30.0,
+            ground: GroundConfig {
+                friction_hz: 20.0,
+                acceleration_hz: 20.0,
+                ..default()
+            },
+            air: AirConfig {
+                speed: 8.0,
+                acceleration_hz: 8.0,
+                ..default()
+            },
+            jump: JumpConfig {
+                height: 2.5,
+                coyote_time: Duration::from_millis(150),
+                input_buffer: Duration::from_millis(200),
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    /// Grounded, realistic movement: jogging speed, real-world gravity, small jumps, and little
+    /// air control.
+    pub fn realistic() -> Self {
+        Self {
+            speed: 5.0,
+            gravity: 9.81,
+            ground: GroundConfig {
+                step_size: 0.35,
+                friction_hz: 8.0,
+                acceleration_hz: 6.0,
+                ..default()
+            },
+            air: AirConfig {
+                speed: 0.5,
+                acceleration_hz: 0.5,
+                ..default()
+            },
+            jump: JumpConfig {
+                height: 0.5,
+                coyote_time: Duration::from_millis(50),
+                ..default()
+            },
+            crouch: CrouchConfig {
+                speed_scale: 0.4,
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    /// Overrides the ground, air, and crouching speeds.
+    pub fn with_speeds(mut self, speed: f32, air_speed: f32, crouch_speed_scale: f32) -> Self {
+        self.speed = speed;
+        self.air.speed = air_speed;
+        self.crouch.speed_scale = crouch_speed_scale;
+        self
+    }
+
+    /// Overrides the ground, air, and water accelerations.
+    pub fn with_acceleration(mut self, ground_hz: f32, air_hz: f32, water_hz: f32) -> Self {
+        self.ground.acceleration_hz = ground_hz;
+        self.air.acceleration_hz = air_hz;
+        self.water.acceleration_hz = water_hz;
+        self
+    }
+
+    /// Overrides the ground friction and the speed below which the character stops.
+    pub fn with_friction(mut self, friction_hz: f32, stop_speed: f32) -> Self {
+        self.ground.friction_hz = friction_hz;
+        self.ground.stop_speed = stop_speed;
+        self
+    }
+
+    /// Overrides the gravity and jump settings.
+    pub fn with_jump(mut self, gravity: f32, jump: JumpConfig) -> Self {
+        self.gravity = gravity;
+        self.jump = jump;
+        self
+    }
+
+    /// The height of the camera above the character's feet, following the crouch transition.
+    pub fn view_height(&self, state: &CharacterControllerState) -> f32 {
+        if state.prone {
+            return self.prone.view_height;
+        }
+        let crouch_amount = state.crouch_amount.clamp(0.0, 1.0);
+        self.standing_view_height
+            + (self.crouch.view_height - self.standing_view_height) * crouch_amount
+    }
+
+    /// Overrides the heights used for crouching and the camera.
+    pub fn with_heights(mut self, standing_view_height: f32, crouch: CrouchConfig) -> Self {
+        self.standing_view_height = standing_view_height;
+        self.crouch = crouch;
+        self
+    }
+
+    /// Overrides the step height and the maximum walkable slope angle in radians.
+    pub fn with_terrain(mut self, step_size: f32, max_walk_angle: f32) -> Self {
+        self.ground.step_size = step_size;
+        self.ground.min_walk_cos = max_walk_angle.cos();
+        self
+    }
+
+    /// The gravity acting on this character, respecting [`Self::use_physics_gravity`].
+    pub fn effective_gravity(&self, physics_gravity: &Gravity) -> f32 {
+        if self.use_physics_gravity {
+            -physics_gravity.0.y
+        } else {
+            self.gravity
+        }
+    }
+
+    /// Rebuilds the [`CharacterControllerDerivedProps`] of `entity` from its current
+    /// [`CharacterController`] and [`Collider`].
+    ///
+    /// This happens automatically when a [`CharacterController`] is added. Call this after
+    /// overwriting the controller of an existing character, e.g. when restoring a deserialized
+    /// [`CharacterController`] from a save game.
+    pub fn rebuild_derived_props(world: &mut World, entity: Entity) -> Result<(), BevyError> {
+        world.run_system_cached_with(setup_collider, entity)?;
+        Ok(())
+    }
+
     pub fn on_add(mut world: DeferredWorld, ctx: HookContext) {
         let has_collider = world.entity(ctx.entity).contains::<Collider>();
 
@@ -279,37 +1071,55 @@ fn setup_collider(
     let standing_height = standing_aabb.max.y - standing_aabb.min.y;
 
     derived.standing_collider = collider.clone();
+    derived.crouching_collider = shorten_collider(collider, cfg.crouch.height);
+    derived.prone_collider = shorten_collider(collider, cfg.prone.height);
+
+    let steps = cfg.crouch.transition_steps;
+    derived.transition_colliders = (1..=steps)
+        .map(|step| {
+            let t = step as f32 / (steps + 1) as f32;
+            let height = cfg.crouch.height + (standing_height - cfg.crouch.height) * t;
+            shorten_collider(collider, height)
+        })
+        .collect();
+}
+
+/// Builds a copy of `collider` that is `height` tall, with its feet in the same place.
+fn shorten_collider(collider: &Collider, height: f32) -> Collider {
+    let standing_aabb = collider.aabb(default(), Rotation::default());
+    let standing_height = standing_aabb.max.y - standing_aabb.min.y;
 
-    let frac = cfg.crouch_height / standing_height;
+    let frac = height / standing_height;
 
-    let mut crouching_collider = Collider::from(SharedShape(Arc::from(
-        derived.standing_collider.shape().clone_dyn(),
-    )));
+    let mut short_collider = Collider::from(SharedShape(Arc::from(collider.shape().clone_dyn())));
 
-    if crouching_collider.shape().as_capsule().is_some() {
-        let capsule = crouching_collider
+    if short_collider.shape().as_capsule().is_some() {
+        let capsule = short_collider
             .shape_mut()
             .make_mut()
             .as_capsule_mut()
             .unwrap();
         let radius = capsule.radius;
-        let new_height = (cfg.crouch_height - radius).max(0.0);
+        let new_height = (height - radius).max(0.0);
         *capsule = Capsule::new_y(new_height / 2.0, radius);
     } else {
         // note: well-behaved shapes like cylinders and cuboids will not actually subdivide when scaled, yay
-        crouching_collider.set_scale(vec3(1.0, frac, 1.0), 16);
+        short_collider.set_scale(vec3(1.0, frac, 1.0), 16);
     }
 
-    derived.crouching_collider = Collider::compound(vec![(
-        Vec3::Y * (cfg.crouch_height - standing_height) / 2.0,
+    // Keep the feet in place, even if the collider is not centered on the character's origin
+    let short_aabb = short_collider.aabb(default(), Rotation::default());
+    Collider::compound(vec![(
+        Vec3::Y * (standing_aabb.min.y - short_aabb.min.y),
         Rotation::default(),
-        crouching_collider,
-    )]);
-
+        short_collider,
+    )])
 }
 
 #[derive(Component, Clone, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct CharacterControllerState {
     pub orientation: Quat,
     /// The velocity of the platform that the character is standing on (or has recently jumped off
@@ -318,11 +1128,49 @@ pub struct CharacterControllerState {
     /// The angular velocity of the platform that the character is standing on (or has recently
     /// jumped off of).
     pub platform_angular_velocity: Vec3,
+    /// Not serialized, as it is detected again on the next tick.
+    #[cfg_attr(feature = "serialize", serde(skip))]
     pub grounded: Option<MoveHitData>,
+    /// Whether the character is at least partially crouched.
     pub crouching: bool,
+    /// Whether the character is lying prone. Prone characters also count as crouching.
+    pub prone: bool,
+    /// Whether the character is sprinting on the ground.
+    pub sprinting: bool,
+    /// Whether the character's [`Jetpack`] is thrusting.
+    pub thrusting: bool,
+    /// Whether the character is jumping out of water onto a ledge. See
+    /// [`WaterConfig::exit_jump_height`].
+    pub water_jumping: bool,
+    /// How far the character is crouched, from `0.0` for standing to `1.0` for fully crouched.
+    /// Takes values in between during a [`CrouchConfig::transition`] or while partially standing
+    /// under a low ceiling, see [`CrouchConfig::partial_stand`].
+    pub crouch_amount: f32,
     pub last_ground: Stopwatch,
     pub last_step_up: Stopwatch,
     pub last_step_down: Stopwatch,
+    /// The outward normal of the wall the character last touched while in the air.
+    pub wall_normal: Option<Vec3>,
+    pub last_wall_touch: Stopwatch,
+    pub last_wall_jump: Stopwatch,
+    /// The number of wall jumps since the character last touched the ground.
+    pub wall_jumps: u32,
+    /// The number of jumps in the air since the character last touched the ground. See
+    /// [`JumpConfig::air_jumps`].
+    pub air_jumps: u32,
+    /// Whether the character is rising from a jump that can still be cut short. See
+    /// [`JumpConfig::release_velocity_scale`].
+    pub jump_rising: bool,
+    /// Where the character is pinned while standing still. See [`GroundConfig::idle_lock`].
+    pub idle_anchor: Option<Vec3>,
+    /// The number of ticks in a row the character has been stuck inside geometry.
+    pub stuck_ticks: u32,
+    /// Where the character was when it got stuck inside geometry.
+    pub stuck_position: Option<Vec3>,
+    /// The rigid body of the moving platform the character is standing on.
+    /// Not serialized, as it is detected again on the next tick.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub platform: Option<Entity>,
 }
 
 impl Default for CharacterControllerState {
@@ -333,13 +1181,190 @@ impl Default for CharacterControllerState {
             orientation: Quat::IDENTITY,
             grounded: None,
             crouching: false,
+            prone: false,
+            sprinting: false,
+            thrusting: false,
+            water_jumping: false,
+            crouch_amount: 0.0,
             last_ground: max_stopwatch(),
             last_step_up: max_stopwatch(),
             last_step_down: max_stopwatch(),
+            wall_normal: None,
+            last_wall_touch: max_stopwatch(),
+            last_wall_jump: max_stopwatch(),
+            wall_jumps: 0,
+            air_jumps: 0,
+            jump_rising: false,
+            idle_anchor: None,
+            stuck_ticks: 0,
+            stuck_position: None,
+            platform: None,
+        }
+    }
+}
+
+
+/// Suspends a character, e.g. while in a menu or while it is pooled, without removing any of its
+/// components.
+///
+/// Disabled characters are not moved, don't react to input or water, don't push rigid bodies,
+/// and their camera is not synced to them.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct CharacterControllerDisabled;
+
+/// Lets a character fly freely through walls without gravity, e.g. for a noclip cheat or a
+/// spectator camera.
+///
+/// The character moves along its look direction, rises with [`Jump`] or [`SwimUp`], and sinks
+/// with [`Crouch`]. It does not collide with anything, touch the ground, or swim. Remove this to
+/// go back to walking. A character that ends up inside geometry is then pushed out by
+/// depenetration, or moved to free space if [`CharacterController::unstuck_ticks`] is set.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct FlyMode {
+    /// The top flying speed, in meters per second.
+    pub speed: f32,
+    /// Multiplies [`Self::speed`] while [`Sprint`] is held.
+    pub sprint_speed_scale: f32,
+    /// How quickly the character speeds up, relative to the wished speed.
+    pub acceleration_hz: f32,
+    /// How quickly the character slows down while there is no input.
+    pub friction_hz: f32,
+}
+
+impl Default for FlyMode {
+    fn default() -> Self {
+        Self {
+            speed: 15.0,
+            sprint_speed_scale: 3.0,
+            acceleration_hz: 10.0,
+            friction_hz: 8.0,
+        }
+    }
+}
+
+/// Overrides how characters treat a collider when they hit it, beyond what the static
+/// [`CharacterController::filter`] can express. Insert this on a collider or on its rigid body.
+/// The collider takes precedence.
+///
+/// For example, put this on the colliders of characters with [`Self::characters`] set to their
+/// team's layer so that teammates can't stand on each other's heads, or on debris to let
+/// characters walk through it.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct CharacterHitRule {
+    /// Whether characters can stand on this collider.
+    pub ground: bool,
+    /// Whether this collider blocks characters. Non-blocking colliders are not recorded in
+    /// [`CharacterControllerOutput::touching_entities`] and are not pushed.
+    ///
+    /// Note that characters are still pushed out of non-blocking colliders they spawn in.
+    pub obstacle: bool,
+    /// The rule only applies to characters whose [`CollisionLayers::memberships`] overlap this.
+    pub characters: LayerMask,
+}
+
+impl Default for CharacterHitRule {
+    fn default() -> Self {
+        Self {
+            ground: true,
+            obstacle: true,
+            characters: LayerMask::ALL,
+        }
+    }
+}
+
+/// Scales how fast characters walk on a collider, e.g. `0.5` for sand or sticky goo and `1.2`
+/// for roads. Insert this on a collider or on its rigid body. The collider takes precedence.
+#[derive(Component, Clone, Copy, Reflect, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct SurfaceSpeedModifier(pub f32);
+
+impl Default for SurfaceSpeedModifier {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Replaces [`GroundConfig::min_walk_cos`] for a collider, e.g. to make a steep ramp walkable or
+/// to make a gentle slope slide. Insert this on a collider or on its rigid body. The collider
+/// takes precedence.
+#[derive(Component, Clone, Copy, Reflect, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct WalkableAngleOverride {
+    /// The cosine of the steepest angle characters can walk on.
+    pub min_walk_cos: f32,
+}
+
+impl WalkableAngleOverride {
+    /// Characters can never stand on the collider.
+    pub const UNWALKABLE: Self = Self {
+        min_walk_cos: f32::INFINITY,
+    };
+
+    /// Makes slopes up to `max_walk_angle` in radians walkable.
+    pub fn from_angle(max_walk_angle: f32) -> Self {
+        Self {
+            min_walk_cos: max_walk_angle.cos(),
         }
     }
 }
 
+/// Makes a collider slippery like ice, so that characters on it speed up slowly and slide for a
+/// while before coming to a stop. Insert this on a collider or on its rigid body. The collider
+/// takes precedence.
+///
+/// Unlike a low [`Friction`] alone, this also reduces how quickly characters can change their
+/// direction, and never lets them stop dead, regardless of the [`GroundConfig::friction_model`].
+#[derive(Component, Clone, Copy, Reflect, Debug, PartialEq)]
+#[reflect(Component)]
+pub struct Slippery {
+    /// Scales [`GroundConfig::acceleration_hz`] and [`GroundConfig::sprint_acceleration_hz`].
+    pub acceleration_scale: f32,
+    /// Scales the ground friction, on top of the [`Friction`] of the collider.
+    pub friction_scale: f32,
+}
+
+impl Default for Slippery {
+    fn default() -> Self {
+        Self {
+            acceleration_scale: 0.15,
+            friction_scale: 0.1,
+        }
+    }
+}
+
+/// Prevents characters from climbing over a collider, e.g. for fences, glass, or out-of-bounds
+/// walls. Insert this on a collider or on its rigid body.
+///
+/// Currently, this stops characters with a [`Vault`] from vaulting over the collider.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct NoClimb;
+
+/// The velocity a character wants to move with this tick, based on its input.
+///
+/// Computed during [`MoveCharactersSystems::Crouch`] and consumed during
+/// [`MoveCharactersSystems::Accelerate`].
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct WishVelocity {
+    /// The wish velocity when walking or in the air, which has no vertical component.
+    pub planar: Vec3,
+    /// The wish velocity when swimming, which follows the look direction.
+    pub spatial: Vec3,
+}
+
+/// Velocity changes from outside of the character's own movement, e.g. explosions or weapon
+/// recoil, that are applied on the next tick.
+///
+/// Add to this instead of writing to [`LinearVelocity`] directly, so that the impulse composes
+/// with jumping, friction, and acceleration. An upward impulse lifts a grounded character off the
+/// ground. Cleared after it was applied during [`MoveCharactersSystems::Accelerate`].
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct CharacterImpulse(pub Vec3);
 
 fn max_stopwatch() -> Stopwatch {
     let mut watch = Stopwatch::new();
@@ -354,15 +1379,37 @@ pub struct CharacterControllerDerivedProps {
     pub standing_collider: Collider,
     /// The collider for the primary movement used when the character is crouching.
     pub crouching_collider: Collider,
+    /// The collider for the primary movement used when the character is prone.
+    pub prone_collider: Collider,
+    /// The colliders used while transitioning between standing and crouching, from the shortest
+    /// to the tallest. See [`CrouchConfig::transition_steps`].
+    pub transition_colliders: Vec<Collider>,
 }
 
 impl CharacterControllerDerivedProps {
     pub fn collider(&self, state: &CharacterControllerState) -> &Collider {
-        if state.crouching {
-            &self.crouching_collider
-        } else {
-            &self.standing_collider
+        if state.prone {
+            return &self.prone_collider;
+        }
+        if state.crouch_amount <= 0.0 {
+            return &self.standing_collider;
+        }
+        if state.crouch_amount >= 1.0 {
+            return &self.crouching_collider;
         }
+        // Level 0 is fully crouched, the last level is standing
+        let levels = self.transition_colliders.len() + 1;
+        let level = ((1.0 - state.crouch_amount) * levels as f32).round() as usize;
+        match level {
+            0 => &self.crouching_collider,
+            level if level >= levels => &self.standing_collider,
+            level => &self.transition_colliders[level - 1],
+        }
+    }
+
+    /// The height of the collider the character currently uses.
+    pub fn height(&self, state: &CharacterControllerState) -> f32 {
+        self.pos_to_head_dist(state) - self.pos_to_feet_dist(state)
     }
 
     pub fn pos_to_head_dist(&self, state: &CharacterControllerState) -> f32 {
@@ -476,6 +1523,10 @@ pub struct TouchingEntity {
     /// To move the shape, use [`Self::distance`] instead.
     #[doc(alias = "time_of_impact")]
     pub collision_distance: f32,
+
+    /// Whether the hit happened while the character was stepping up. No forces are applied to
+    /// dynamic rigid bodies for these hits, as stepping onto them would otherwise kick them away.
+    pub stepping_up: bool,
 }
 impl From<MoveAndSlideHitData<'_>> for TouchingEntity {
     fn from(value: MoveAndSlideHitData<'_>) -> Self {
@@ -487,6 +1538,7 @@ impl From<MoveAndSlideHitData<'_>> for TouchingEntity {
             character_position: *value.position,
             character_velocity: *value.velocity,
             collision_distance: value.collision_distance,
+            stepping_up: false,
         }
     }
 }
@@ -0,0 +1,200 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::time::Duration;
+
+use crate::prelude::*;
+
+/// Moves kinematic bodies along their [`PathMover`].
+pub struct AhoyPathMoverPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyPathMoverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            move_along_paths.before(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// Moves a kinematic rigid body along a path of waypoints, e.g. for elevators, trains, or other
+/// moving platforms. Characters standing on it move along.
+///
+/// The body travels to the waypoints in order, starting with the first one.
+#[derive(Component, Clone, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(RigidBody::Kinematic, TransformInterpolation)]
+pub struct PathMover {
+    pub waypoints: Vec<PathWaypoint>,
+    pub mode: PathMode,
+    /// The angular velocity the body spins with while following the path.
+    pub angular_velocity: Vec3,
+    /// The waypoint the body is currently traveling to.
+    pub target: usize,
+    reversed: bool,
+    pause: Duration,
+}
+
+impl PathMover {
+    pub fn new(waypoints: impl IntoIterator<Item = PathWaypoint>, mode: PathMode) -> Self {
+        Self {
+            waypoints: waypoints.into_iter().collect(),
+            mode,
+            ..default()
+        }
+    }
+
+    /// Whether the body reached the end of a [`PathMode::Once`] path.
+    pub fn is_finished(&self) -> bool {
+        self.target >= self.waypoints.len()
+    }
+
+    /// Advances to the next waypoint according to [`Self::mode`].
+    fn advance(&mut self) {
+        let len = self.waypoints.len();
+        match self.mode {
+            PathMode::Once => self.target += 1,
+            PathMode::Loop => self.target = (self.target + 1) % len,
+            PathMode::PingPong => {
+                if len < 2 {
+                    return;
+                }
+                if (self.reversed && self.target == 0) || (!self.reversed && self.target == len - 1)
+                {
+                    self.reversed = !self.reversed;
+                }
+                if self.reversed {
+                    self.target -= 1;
+                } else {
+                    self.target += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A point on the path of a [`PathMover`].
+#[derive(Clone, Copy, Reflect, Debug)]
+pub struct PathWaypoint {
+    /// The position of the waypoint, expressed in world space.
+    pub position: Vec3,
+    /// The speed the body travels to this waypoint with.
+    pub speed: f32,
+    /// How long the body waits once it reached this waypoint.
+    pub pause: Duration,
+}
+
+impl PathWaypoint {
+    pub fn new(position: Vec3, speed: f32) -> Self {
+        Self {
+            position,
+            speed,
+            pause: Duration::ZERO,
+        }
+    }
+
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+}
+
+/// What a [`PathMover`] does after reaching its last waypoint.
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq, Eq)]
+pub enum PathMode {
+    /// Travel back to the first waypoint and start over.
+    #[default]
+    Loop,
+    /// Travel the waypoints back in reverse order, then forward again.
+    PingPong,
+    /// Stop at the last waypoint.
+    Once,
+}
+
+/// Below this distance, a waypoint counts as reached.
+const ARRIVAL_DISTANCE: f32 = 0.001;
+
+fn move_along_paths(
+    mut movers: Query<(
+        &mut PathMover,
+        &Position,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for (mut mover, position, mut velocity, mut angular_velocity) in &mut movers {
+        velocity.0 = Vec3::ZERO;
+        angular_velocity.0 = mover.angular_velocity;
+        if dt <= 0.0 || mover.is_finished() {
+            continue;
+        }
+        if !mover.pause.is_zero() {
+            mover.pause = mover.pause.saturating_sub(time.delta());
+            continue;
+        }
+
+        let waypoint = mover.waypoints[mover.target];
+        let to_target = waypoint.position - position.0;
+        let distance = to_target.length();
+        if distance <= ARRIVAL_DISTANCE {
+            mover.pause = waypoint.pause;
+            mover.advance();
+            continue;
+        }
+        // Don't overshoot the waypoint
+        let speed = waypoint.speed.min(distance / dt);
+        velocity.0 = to_target / distance * speed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mover(len: usize, mode: PathMode) -> PathMover {
+        PathMover::new(
+            (0..len).map(|i| PathWaypoint::new(Vec3::X * i as f32, 1.0)),
+            mode,
+        )
+    }
+
+    fn targets(mover: &mut PathMover, steps: usize) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                mover.advance();
+                mover.target
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loop_wraps_around() {
+        let mut mover = mover(3, PathMode::Loop);
+        assert_eq!(targets(&mut mover, 5), [1, 2, 0, 1, 2]);
+        assert!(!mover.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_reverses_at_both_ends() {
+        let mut mover = mover(3, PathMode::PingPong);
+        assert_eq!(targets(&mut mover, 6), [1, 2, 1, 0, 1, 2]);
+        assert!(!mover.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_stays_on_a_single_waypoint() {
+        let mut mover = mover(1, PathMode::PingPong);
+        assert_eq!(targets(&mut mover, 2), [0, 0]);
+    }
+
+    #[test]
+    fn once_finishes_after_the_last_waypoint() {
+        let mut mover = mover(2, PathMode::Once);
+        mover.advance();
+        assert!(!mover.is_finished());
+        mover.advance();
+        assert!(mover.is_finished());
+    }
+}
@@ -0,0 +1,210 @@
+use bevy_camera::Projection;
+use core::f32::consts::TAU;
+
+use crate::{
+    camera::{CharacterControllerCamera, CharacterControllerCameraOf},
+    input::{PhotoRoll, PhotoZoom, TogglePhotoMode},
+    prelude::*,
+};
+
+/// Lets players detach the camera from their character to take screenshots.
+pub struct AhoyPhotoModePlugin;
+
+impl Plugin for AhoyPhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(toggle_photo_mode)
+            .add_observer(enter_photo_mode)
+            .add_observer(exit_photo_mode)
+            .add_observer(fly_photo_camera)
+            .add_observer(rotate_photo_camera)
+            .add_observer(roll_photo_camera)
+            .add_observer(zoom_photo_camera);
+    }
+}
+
+/// Puts a [`CharacterControllerCameraOf`] into photo mode while present.
+///
+/// The character is frozen with [`CharacterControllerDisabled`], keeping its whole movement state,
+/// and the camera flies freely with the character's [`Movement`] and [`RotateCamera`] actions,
+/// rolls with [`PhotoRoll`], and zooms with [`PhotoZoom`]. Removing this restores the camera
+/// exactly as it was, and unfreezes the character.
+///
+/// Insert and remove this yourself, or fire [`TogglePhotoMode`] on the character.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct PhotoMode {
+    /// How fast the camera flies, in meters per second.
+    pub speed: f32,
+    /// How fast the camera rolls, in radians per second.
+    pub roll_speed: f32,
+    /// How fast the field of view changes, in radians per second.
+    pub zoom_speed: f32,
+    /// The smallest and largest field of view that can be zoomed to, in radians.
+    pub fov_range: (f32, f32),
+    #[reflect(ignore)]
+    restore: Option<PhotoModeRestore>,
+}
+
+impl Default for PhotoMode {
+    fn default() -> Self {
+        Self {
+            speed: 5.0,
+            roll_speed: 1.0,
+            zoom_speed: 1.0,
+            fov_range: (0.1, 2.5),
+            restore: None,
+        }
+    }
+}
+
+/// What to restore when leaving photo mode.
+#[derive(Clone, Copy, Debug)]
+struct PhotoModeRestore {
+    transform: Transform,
+    fov: Option<f32>,
+    /// Whether the character was only disabled by the photo mode.
+    enable_character: bool,
+}
+
+fn toggle_photo_mode(
+    toggle: On<Start<TogglePhotoMode>>,
+    cameras: Query<&CharacterControllerCamera>,
+    photo_modes: Query<(), With<PhotoMode>>,
+    mut commands: Commands,
+) {
+    let Ok(camera) = cameras.get(toggle.context) else {
+        return;
+    };
+    if photo_modes.contains(camera.get()) {
+        commands.entity(camera.get()).remove::<PhotoMode>();
+    } else {
+        commands.entity(camera.get()).insert(PhotoMode::default());
+    }
+}
+
+fn enter_photo_mode(
+    add: On<Add, PhotoMode>,
+    mut cameras: Query<(
+        &mut PhotoMode,
+        &Transform,
+        Option<&Projection>,
+        &CharacterControllerCameraOf,
+    )>,
+    disabled: Query<(), With<CharacterControllerDisabled>>,
+    mut commands: Commands,
+) {
+    let Ok((mut photo_mode, transform, projection, camera)) = cameras.get_mut(add.entity) else {
+        return;
+    };
+    let character = camera.character_controller;
+    let enable_character = !disabled.contains(character);
+    photo_mode.restore = Some(PhotoModeRestore {
+        transform: *transform,
+        fov: match projection {
+            Some(Projection::Perspective(perspective)) => Some(perspective.fov),
+            _ => None,
+        },
+        enable_character,
+    });
+    commands
+        .entity(character)
+        .insert(CharacterControllerDisabled);
+}
+
+fn exit_photo_mode(
+    remove: On<Remove, PhotoMode>,
+    mut cameras: Query<(
+        &PhotoMode,
+        &mut Transform,
+        Option<&mut Projection>,
+        &CharacterControllerCameraOf,
+    )>,
+    mut commands: Commands,
+) {
+    let Ok((photo_mode, mut transform, projection, camera)) = cameras.get_mut(remove.entity) else {
+        return;
+    };
+    let Some(restore) = photo_mode.restore else {
+        return;
+    };
+    *transform = restore.transform;
+    if let (Some(mut projection), Some(fov)) = (projection, restore.fov)
+        && let Projection::Perspective(perspective) = projection.as_mut()
+    {
+        perspective.fov = fov;
+    }
+    if restore.enable_character {
+        commands
+            .entity(camera.character_controller)
+            .try_remove::<CharacterControllerDisabled>();
+    }
+}
+
+fn fly_photo_camera(
+    movement: On<Fire<Movement>>,
+    characters: Query<&CharacterControllerCamera>,
+    mut cameras: Query<(&PhotoMode, &mut Transform)>,
+    time: Res<Time>,
+) {
+    let Ok(camera) = characters.get(movement.context) else {
+        return;
+    };
+    let Ok((photo_mode, mut transform)) = cameras.get_mut(camera.get()) else {
+        return;
+    };
+    let direction = transform.rotation * vec3(movement.value.x, 0.0, -movement.value.y);
+    transform.translation += direction * photo_mode.speed * time.delta_secs();
+}
+
+fn rotate_photo_camera(
+    rotate: On<Fire<RotateCamera>>,
+    characters: Query<&CharacterControllerCamera>,
+    mut cameras: Query<&mut Transform, With<PhotoMode>>,
+) {
+    let Ok(camera) = characters.get(rotate.context) else {
+        return;
+    };
+    let Ok(mut transform) = cameras.get_mut(camera.get()) else {
+        return;
+    };
+    let (mut yaw, mut pitch, roll) = transform.rotation.to_euler(EulerRot::YXZ);
+    let delta = -rotate.value;
+    yaw += delta.x.to_radians();
+    pitch = (pitch + delta.y.to_radians()).clamp(-TAU / 4.0 + 0.01, TAU / 4.0 - 0.01);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll);
+}
+
+fn roll_photo_camera(
+    roll: On<Fire<PhotoRoll>>,
+    characters: Query<&CharacterControllerCamera>,
+    mut cameras: Query<(&PhotoMode, &mut Transform)>,
+    time: Res<Time>,
+) {
+    let Ok(camera) = characters.get(roll.context) else {
+        return;
+    };
+    let Ok((photo_mode, mut transform)) = cameras.get_mut(camera.get()) else {
+        return;
+    };
+    transform.rotate_local_z(roll.value * photo_mode.roll_speed * time.delta_secs());
+}
+
+fn zoom_photo_camera(
+    zoom: On<Fire<PhotoZoom>>,
+    characters: Query<&CharacterControllerCamera>,
+    mut cameras: Query<(&PhotoMode, &mut Projection)>,
+    time: Res<Time>,
+) {
+    let Ok(camera) = characters.get(zoom.context) else {
+        return;
+    };
+    let Ok((photo_mode, mut projection)) = cameras.get_mut(camera.get()) else {
+        return;
+    };
+    let Projection::Perspective(perspective) = projection.as_mut() else {
+        return;
+    };
+    let (min_fov, max_fov) = photo_mode.fov_range;
+    perspective.fov = (perspective.fov - zoom.value * photo_mode.zoom_speed * time.delta_secs())
+        .clamp(min_fov, max_fov);
+}
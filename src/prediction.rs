@@ -0,0 +1,218 @@
+use bevy_ecs::{error::BevyError, intern::Interned, schedule::ScheduleLabel};
+use core::time::Duration;
+use std::collections::VecDeque;
+
+use crate::{MoveCharactersSystems, prelude::*, step_character};
+
+/// Records the [`PredictionHistory`] of every predicted character.
+///
+/// This is independent of any networking library: feed it the ticks and authoritative
+/// [`CharacterSnapshot`]s of whatever replication you use, e.g. `lightyear` or `bevy_replicon`.
+pub struct AhoyPredictionPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyPredictionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            record_predicted_ticks
+                .in_set(AhoySystems::MoveCharacters)
+                .before(MoveCharactersSystems::Depenetrate),
+        );
+    }
+}
+
+/// Marks a character as locally predicted and keeps the ticks it simulated, so that it can be
+/// corrected with [`reconcile_character`].
+#[derive(Component, Clone, Debug)]
+pub struct PredictionHistory {
+    /// The tick that is simulated next. It is incremented after every simulated tick, so set it
+    /// once to the tick of your networking library.
+    pub tick: u32,
+    /// How many ticks are kept for re-simulation. Corrections for older ticks are ignored.
+    pub capacity: usize,
+    ticks: VecDeque<PredictedTick>,
+}
+
+impl Default for PredictionHistory {
+    fn default() -> Self {
+        Self {
+            tick: 0,
+            capacity: 64,
+            ticks: VecDeque::new(),
+        }
+    }
+}
+
+impl PredictionHistory {
+    /// The predicted state at the start of `tick`, if it is still in the history.
+    pub fn get(&self, tick: u32) -> Option<&CharacterSnapshot> {
+        self.ticks
+            .iter()
+            .find(|predicted| predicted.tick == tick)
+            .map(|predicted| &predicted.snapshot)
+    }
+
+    fn push(&mut self, snapshot: CharacterSnapshot, delta: Duration) {
+        self.ticks.push_back(PredictedTick {
+            tick: self.tick,
+            snapshot,
+            delta,
+        });
+        while self.ticks.len() > self.capacity {
+            self.ticks.pop_front();
+        }
+        self.tick = self.tick.wrapping_add(1);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PredictedTick {
+    tick: u32,
+    /// The state at the start of the tick, including the input that was simulated.
+    snapshot: CharacterSnapshot,
+    delta: Duration,
+}
+
+fn record_predicted_ticks(
+    world: &mut World,
+    mut characters: Local<
+        QueryState<
+            Entity,
+            (
+                With<PredictionHistory>,
+                Without<CharacterControllerDisabled>,
+            ),
+        >,
+    >,
+) {
+    let delta = world.resource::<Time>().delta();
+    let entities = characters.iter(world).collect::<Vec<_>>();
+    for entity in entities {
+        let Some(snapshot) = CharacterSnapshot::capture(world, entity) else {
+            continue;
+        };
+        if let Some(mut history) = world.get_mut::<PredictionHistory>(entity) {
+            history.push(snapshot, delta);
+        }
+    }
+}
+
+/// Corrects a predicted character with the authoritative state the server sent for it.
+///
+/// `authoritative` is the state of the character at the end of `tick`. The character is reset to
+/// it, and all ticks predicted after `tick` are re-simulated with their recorded inputs using
+/// [`step_character`]. Corrections for ticks that are no longer in the [`PredictionHistory`] are
+/// ignored.
+pub fn reconcile_character(
+    world: &mut World,
+    entity: Entity,
+    tick: u32,
+    authoritative: &CharacterSnapshot,
+) -> Result<(), BevyError> {
+    let Some(mut history) = world.get_mut::<PredictionHistory>(entity) else {
+        return Ok(());
+    };
+    if !history.ticks.iter().any(|predicted| predicted.tick == tick) {
+        return Ok(());
+    }
+    while history
+        .ticks
+        .front()
+        .is_some_and(|predicted| predicted.tick != tick)
+    {
+        history.ticks.pop_front();
+    }
+    history.ticks.pop_front();
+    let mut replay = core::mem::take(&mut history.ticks);
+
+    authoritative.restore(world, entity)?;
+    for predicted in &mut replay {
+        let mut snapshot = CharacterSnapshot::capture(world, entity)
+            .ok_or("the predicted character is missing its controller components")?;
+        snapshot.input = predicted.snapshot.input.clone();
+        snapshot.water = predicted.snapshot.water;
+        snapshot.look = predicted.snapshot.look.clone();
+        snapshot.restore(world, entity)?;
+        predicted.snapshot = snapshot;
+        step_character(world, entity, predicted.delta)?;
+    }
+
+    if let Some(mut history) = world.get_mut::<PredictionHistory>(entity) {
+        history.ticks = replay;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(x: f32) -> CharacterSnapshot {
+        CharacterSnapshot {
+            transform: Transform::from_xyz(x, 0.0, 0.0),
+            velocity: default(),
+            state: default(),
+            input: default(),
+            water: default(),
+            wish: default(),
+            look: None,
+        }
+    }
+
+    fn history(capacity: usize, ticks: usize) -> PredictionHistory {
+        let mut history = PredictionHistory {
+            capacity,
+            ..default()
+        };
+        for i in 0..ticks {
+            history.push(snapshot(i as f32), Duration::from_millis(16));
+        }
+        history
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_ticks() {
+        let history = history(3, 5);
+        assert_eq!(history.tick, 5);
+        assert!(history.get(1).is_none());
+        assert_eq!(history.get(2).unwrap().transform.translation.x, 2.0);
+        assert_eq!(history.get(4).unwrap().transform.translation.x, 4.0);
+        assert!(history.get(5).is_none());
+    }
+
+    #[test]
+    fn history_tick_wraps_around() {
+        let mut history = PredictionHistory {
+            tick: u32::MAX,
+            ..default()
+        };
+        history.push(snapshot(0.0), Duration::ZERO);
+        history.push(snapshot(1.0), Duration::ZERO);
+        assert_eq!(history.tick, 1);
+        assert!(history.get(u32::MAX).is_some());
+        assert!(history.get(0).is_some());
+    }
+
+    #[test]
+    fn reconcile_ignores_ticks_outside_the_history() {
+        let mut world = World::new();
+        let entity = world.spawn(history(3, 5)).id();
+
+        reconcile_character(&mut world, entity, 1, &snapshot(100.0)).unwrap();
+        reconcile_character(&mut world, entity, 5, &snapshot(100.0)).unwrap();
+
+        let history = world.get::<PredictionHistory>(entity).unwrap();
+        assert_eq!(history.ticks.len(), 3);
+        assert!(world.get::<Transform>(entity).is_none());
+    }
+
+    #[test]
+    fn reconcile_ignores_characters_without_history() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        reconcile_character(&mut world, entity, 0, &snapshot(100.0)).unwrap();
+        assert!(world.get::<Transform>(entity).is_none());
+    }
+}
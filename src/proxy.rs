@@ -0,0 +1,105 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::time::Duration;
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+
+/// Moves [`ProxyCharacter`]s by interpolating between their received snapshots.
+pub struct AhoyProxyPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyProxyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            interpolate_proxies.before(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// Marks a character that is positioned by the network instead of simulated locally, e.g. other
+/// players on a client.
+///
+/// The movement of proxy characters is skipped, and they don't push dynamic rigid bodies. Their
+/// [`WaterState`], ground detection, and camera are still updated, so that animations and effects
+/// work like for any other character. Their [`Transform`] and [`LinearVelocity`] are
+/// interpolated between the snapshots [pushed](Self::push) to them, [`Self::delay`] in the past.
+#[derive(Component, Clone, Debug)]
+pub struct ProxyCharacter {
+    /// How far in the past the snapshots are rendered. Should be a bit more than the interval
+    /// between received snapshots, so that there is always a newer snapshot to interpolate to.
+    pub delay: Duration,
+    snapshots: VecDeque<ProxySnapshot>,
+}
+
+impl Default for ProxyCharacter {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(100),
+            snapshots: VecDeque::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ProxySnapshot {
+    time: Duration,
+    translation: Vec3,
+    rotation: Quat,
+    velocity: Vec3,
+}
+
+impl ProxyCharacter {
+    /// Adds a received snapshot of the character's transform and velocity.
+    ///
+    /// `time` is when the snapshot was received, as [`Time::elapsed`] of the schedule the
+    /// character is moved in. Snapshots older than the newest one are ignored.
+    pub fn push(&mut self, time: Duration, transform: Transform, velocity: Vec3) {
+        if self.snapshots.back().is_some_and(|last| last.time >= time) {
+            return;
+        }
+        self.snapshots.push_back(ProxySnapshot {
+            time,
+            translation: transform.translation,
+            rotation: transform.rotation,
+            velocity,
+        });
+    }
+
+    fn sample(&mut self, time: Duration) -> Option<ProxySnapshot> {
+        // Keep the last snapshot before `time` around to interpolate from
+        while self.snapshots.get(1).is_some_and(|next| next.time <= time) {
+            self.snapshots.pop_front();
+        }
+        let from = *self.snapshots.front()?;
+        let Some(to) = self.snapshots.get(1) else {
+            return Some(from);
+        };
+        let Some(elapsed) = time.checked_sub(from.time) else {
+            return Some(from);
+        };
+        let t = elapsed.as_secs_f32() / (to.time - from.time).as_secs_f32();
+        Some(ProxySnapshot {
+            time,
+            translation: from.translation.lerp(to.translation, t),
+            rotation: from.rotation.slerp(to.rotation, t),
+            velocity: from.velocity.lerp(to.velocity, t),
+        })
+    }
+}
+
+fn interpolate_proxies(
+    mut proxies: Query<(&mut ProxyCharacter, &mut Transform, &mut LinearVelocity)>,
+    time: Res<Time>,
+) {
+    for (mut proxy, mut transform, mut velocity) in &mut proxies {
+        let render_time = time.elapsed().saturating_sub(proxy.delay);
+        let Some(snapshot) = proxy.sample(render_time) else {
+            continue;
+        };
+        transform.translation = snapshot.translation;
+        transform.rotation = snapshot.rotation;
+        velocity.0 = snapshot.velocity;
+    }
+}
@@ -0,0 +1,191 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use bevy_time::Stopwatch;
+use core::time::Duration;
+
+use crate::prelude::*;
+
+/// Times the runs of characters through [`StartZone`]s, [`CheckpointZone`]s, and [`EndZone`]s.
+pub struct AhoyRacePlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyRacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            tick_run_timers.after(AhoySystems::MoveCharacters),
+        )
+        .add_observer(reset_run_in_start_zone)
+        .add_observer(start_run_on_leaving_start_zone)
+        .add_observer(split_run_at_checkpoint)
+        .add_observer(finish_run_in_end_zone);
+    }
+}
+
+/// A sensor that resets the [`RunTimer`] of characters inside it, and starts it once they leave.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct StartZone;
+
+/// A sensor that records a split in the [`RunTimer`] of characters entering it during a run.
+///
+/// Every checkpoint is only split at once per run.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct CheckpointZone;
+
+/// A sensor that stops the [`RunTimer`] of characters entering it during a run.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct EndZone;
+
+/// Times how long a character takes from leaving a [`StartZone`] to entering an [`EndZone`].
+///
+/// Only characters with this component are timed.
+#[derive(Component, Clone, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct RunTimer {
+    /// The time since the run started. Stays at the final time once the run finished.
+    pub elapsed: Stopwatch,
+    /// The checkpoints reached during the run, in order, together with the time they were
+    /// reached at.
+    pub splits: Vec<(Entity, Duration)>,
+    running: bool,
+}
+
+impl RunTimer {
+    /// Whether the character is currently on a run.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Stops the run without finishing it and resets the time.
+    pub fn reset(&mut self) {
+        self.elapsed.reset();
+        self.splits.clear();
+        self.running = false;
+    }
+}
+
+/// Triggered when a character left a [`StartZone`] and its [`RunTimer`] started.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct RunStarted {
+    /// The character.
+    pub entity: Entity,
+}
+
+/// Triggered when a character on a run reached a [`CheckpointZone`].
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct RunSplit {
+    /// The character.
+    pub entity: Entity,
+    /// The checkpoint that was reached.
+    pub checkpoint: Entity,
+    /// The time since the run started.
+    pub time: Duration,
+    /// How many checkpoints were reached before this one during the run.
+    pub index: usize,
+}
+
+/// Triggered when a character on a run reached an [`EndZone`].
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct RunFinished {
+    /// The character.
+    pub entity: Entity,
+    /// The final time of the run.
+    pub time: Duration,
+}
+
+fn tick_run_timers(mut timers: Query<&mut RunTimer>, time: Res<Time>) {
+    for mut timer in &mut timers {
+        if timer.running {
+            timer.elapsed.tick(time.delta());
+        }
+    }
+}
+
+fn reset_run_in_start_zone(
+    start: On<CollisionStart>,
+    zones: Query<(), With<StartZone>>,
+    mut timers: Query<&mut RunTimer>,
+) {
+    if !zones.contains(start.collider1) {
+        return;
+    }
+    let Ok(mut timer) = timers.get_mut(start.body2.unwrap_or(start.collider2)) else {
+        return;
+    };
+    timer.reset();
+}
+
+fn start_run_on_leaving_start_zone(
+    end: On<CollisionEnd>,
+    zones: Query<(), With<StartZone>>,
+    mut timers: Query<&mut RunTimer>,
+    mut commands: Commands,
+) {
+    if !zones.contains(end.collider1) {
+        return;
+    }
+    let entity = end.body2.unwrap_or(end.collider2);
+    let Ok(mut timer) = timers.get_mut(entity) else {
+        return;
+    };
+    timer.reset();
+    timer.running = true;
+    commands.trigger(RunStarted { entity });
+}
+
+fn split_run_at_checkpoint(
+    start: On<CollisionStart>,
+    zones: Query<(), With<CheckpointZone>>,
+    mut timers: Query<&mut RunTimer>,
+    mut commands: Commands,
+) {
+    let checkpoint = start.collider1;
+    if !zones.contains(checkpoint) {
+        return;
+    }
+    let entity = start.body2.unwrap_or(start.collider2);
+    let Ok(mut timer) = timers.get_mut(entity) else {
+        return;
+    };
+    if !timer.running || timer.splits.iter().any(|(split, _)| *split == checkpoint) {
+        return;
+    }
+    let time = timer.elapsed.elapsed();
+    let index = timer.splits.len();
+    timer.splits.push((checkpoint, time));
+    commands.trigger(RunSplit {
+        entity,
+        checkpoint,
+        time,
+        index,
+    });
+}
+
+fn finish_run_in_end_zone(
+    start: On<CollisionStart>,
+    zones: Query<(), With<EndZone>>,
+    mut timers: Query<&mut RunTimer>,
+    mut commands: Commands,
+) {
+    if !zones.contains(start.collider1) {
+        return;
+    }
+    let entity = start.body2.unwrap_or(start.collider2);
+    let Ok(mut timer) = timers.get_mut(entity) else {
+        return;
+    };
+    if !timer.running {
+        return;
+    }
+    timer.running = false;
+    commands.trigger(RunFinished {
+        entity,
+        time: timer.elapsed.elapsed(),
+    });
+}
@@ -0,0 +1,60 @@
+use bevy_ecs::error::BevyError;
+
+use crate::{CharacterControllerState, prelude::*};
+
+/// Marks a character that is currently a ragdoll, see [`enter_ragdoll`].
+///
+/// Ragdolls are simulated by Avian as dynamic rigid bodies instead of being moved by the KCC.
+#[derive(Component, Clone, Copy, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct Ragdoll;
+
+/// Turns the character `entity` into a ragdoll, e.g. when it dies.
+///
+/// The character becomes a dynamic rigid body that keeps its current velocity. If the character
+/// has a joint hierarchy of dynamic rigid bodies among its descendants, e.g. the limbs of a
+/// ragdoll, they inherit the velocity as well. Its camera keeps following it.
+pub fn enter_ragdoll(world: &mut World, entity: Entity) -> Result<(), BevyError> {
+    let mut character = world.get_entity_mut(entity)?;
+    let velocity = character
+        .get::<LinearVelocity>()
+        .copied()
+        .unwrap_or_default();
+    character
+        .insert((Ragdoll, RigidBody::Dynamic))
+        .remove::<CustomPositionIntegration>();
+
+    let descendants = world
+        .query::<&Children>()
+        .query(world)
+        .iter_descendants(entity)
+        .collect::<Vec<_>>();
+    for descendant in descendants {
+        let mut descendant = world.entity_mut(descendant);
+        if descendant.contains::<RigidBody>() {
+            descendant.insert(velocity);
+        }
+    }
+    Ok(())
+}
+
+/// Gives control of the ragdoll `entity` back to the KCC, e.g. when it is revived.
+///
+/// The character is stood back up with its current yaw and comes to a standstill.
+pub fn exit_ragdoll(world: &mut World, entity: Entity) -> Result<(), BevyError> {
+    let mut character = world.get_entity_mut(entity)?;
+    if let Some(mut transform) = character.get_mut::<Transform>() {
+        let (yaw, _, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        transform.rotation = Quat::from_rotation_y(yaw);
+    }
+    if let Some(mut state) = character.get_mut::<CharacterControllerState>() {
+        state.grounded = None;
+    }
+    character.remove::<Ragdoll>().insert((
+        RigidBody::Kinematic,
+        CustomPositionIntegration,
+        LinearVelocity::ZERO,
+        AngularVelocity::ZERO,
+    ));
+    Ok(())
+}
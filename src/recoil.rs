@@ -0,0 +1,53 @@
+use core::f32::consts::FRAC_PI_2;
+
+use crate::{CharacterControllerState, CharacterLook, kcc::forward, prelude::*};
+
+/// Applies [`Recoil`] to characters.
+pub struct AhoyRecoilPlugin;
+
+impl Plugin for AhoyRecoilPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(apply_recoil);
+    }
+}
+
+/// Trigger this to kick a character back, e.g. when it fires a weapon.
+///
+/// The character is pushed opposite to where it looks through its [`CharacterImpulse`], so firing
+/// a strong weapon at the ground launches it into the air, rocket-jump style. Its view is kicked
+/// up at the same time.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct Recoil {
+    /// The character that fired.
+    pub entity: Entity,
+    /// How far the view is kicked, as yaw and pitch in radians. Positive pitch kicks it up.
+    pub view_punch: Vec2,
+    /// The speed the character is pushed back with.
+    pub knockback: f32,
+}
+
+fn apply_recoil(
+    recoil: On<Recoil>,
+    mut characters: Query<
+        (
+            &CharacterControllerState,
+            &mut CharacterImpulse,
+            Option<&mut CharacterLook>,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+) {
+    let Ok((state, mut impulse, look)) = characters.get_mut(recoil.entity) else {
+        return;
+    };
+    let direction = match &look {
+        Some(look) => look.to_quat() * Vec3::NEG_Z,
+        None => forward(state.orientation),
+    };
+    impulse.0 -= direction * recoil.knockback;
+
+    if let Some(mut look) = look {
+        look.yaw += recoil.view_punch.x;
+        look.pitch = (look.pitch + recoil.view_punch.y).clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+    }
+}
@@ -0,0 +1,217 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use bevy_time::Stopwatch;
+use std::collections::BTreeMap;
+
+use crate::{CharacterLook, input::AccumulatedInput, prelude::*};
+
+/// Drives characters with [`RemoteInput`] from their received [`InputFrame`]s instead of local
+/// devices, e.g. for the characters of connected clients on a dedicated server.
+pub struct AhoyRemoteInputPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyRemoteInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            apply_remote_input.before(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// The input of a character for a single tick, as sent by a client.
+#[derive(Clone, Reflect, PartialEq, Debug, Default)]
+pub struct InputFrame {
+    /// The tick this input is meant for.
+    pub tick: u32,
+    /// The movement input, relative to the look direction. See [`Movement`].
+    pub movement: Option<Vec2>,
+    pub jump: bool,
+    /// Whether the jump input is still held, for cutting jumps short when it is released.
+    pub jump_held: bool,
+    pub crouch: bool,
+    pub sprint: bool,
+    pub prone: bool,
+    pub thrust: bool,
+    pub swim_up: bool,
+    pub look: Option<CharacterLook>,
+}
+
+/// A buffer of [`InputFrame`]s received for a character, consumed one per tick.
+///
+/// Frames that arrive twice or too late are dropped. When the frame for a tick is missing, the
+/// last frame is repeated without its jump, so that held inputs like movement and crouching
+/// keep going while a jump is never executed twice.
+#[derive(Component, Clone, Debug)]
+pub struct RemoteInput {
+    /// The maximum number of buffered frames. Frames too far in the future are dropped.
+    pub capacity: usize,
+    frames: BTreeMap<u32, InputFrame>,
+    next_tick: Option<u32>,
+    last: Option<InputFrame>,
+    missed_frames: u32,
+}
+
+impl Default for RemoteInput {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            frames: BTreeMap::new(),
+            next_tick: None,
+            last: None,
+            missed_frames: 0,
+        }
+    }
+}
+
+impl RemoteInput {
+    /// Buffers a received frame. Returns `false` if it was dropped as a duplicate, because it
+    /// arrived too late, or because the buffer is full.
+    pub fn push(&mut self, frame: InputFrame) -> bool {
+        if self
+            .next_tick
+            .is_some_and(|next_tick| frame.tick < next_tick)
+            || self.frames.contains_key(&frame.tick)
+            || self.frames.len() >= self.capacity
+        {
+            return false;
+        }
+        self.frames.insert(frame.tick, frame);
+        true
+    }
+
+    /// The tick whose frame is consumed next, if any frame was received yet.
+    pub fn next_tick(&self) -> Option<u32> {
+        self.next_tick
+    }
+
+    /// How many ticks had to repeat the last frame because their own frame was missing.
+    pub fn missed_frames(&self) -> u32 {
+        self.missed_frames
+    }
+
+    fn pop(&mut self) -> Option<InputFrame> {
+        let tick = self
+            .next_tick
+            .or_else(|| self.frames.keys().next().copied())?;
+        self.next_tick = Some(tick.wrapping_add(1));
+        let frame = match self.frames.remove(&tick) {
+            Some(frame) => frame,
+            None => {
+                self.missed_frames += 1;
+                InputFrame {
+                    tick,
+                    jump: false,
+                    ..self.last.clone()?
+                }
+            }
+        };
+        self.last = Some(frame.clone());
+        Some(frame)
+    }
+}
+
+fn apply_remote_input(
+    mut characters: Query<
+        (
+            &mut RemoteInput,
+            &mut AccumulatedInput,
+            Option<&mut CharacterLook>,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+) {
+    for (mut remote, mut input, look) in &mut characters {
+        let Some(frame) = remote.pop() else {
+            continue;
+        };
+        input.last_movement = frame.movement;
+        if frame.jump {
+            input.jumped = Some(Stopwatch::new());
+        }
+        input.jump_held = frame.jump_held;
+        input.crouched = frame.crouch;
+        input.sprinted = frame.sprint;
+        input.proned = frame.prone;
+        input.thrusted = frame.thrust;
+        input.swim_up = frame.swim_up;
+        if let (Some(mut look), Some(frame_look)) = (look, frame.look) {
+            *look = frame_look;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(tick: u32) -> InputFrame {
+        InputFrame {
+            tick,
+            jump: true,
+            movement: Some(Vec2::Y),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn pops_frames_in_tick_order() {
+        let mut remote = RemoteInput::default();
+        assert!(remote.push(frame(12)));
+        assert!(remote.push(frame(10)));
+        assert!(remote.push(frame(11)));
+
+        let ticks: Vec<_> = (0..3).map(|_| remote.pop().unwrap().tick).collect();
+        assert_eq!(ticks, [10, 11, 12]);
+        assert_eq!(remote.next_tick(), Some(13));
+        assert_eq!(remote.missed_frames(), 0);
+    }
+
+    #[test]
+    fn drops_duplicate_and_late_frames() {
+        let mut remote = RemoteInput::default();
+        assert!(remote.push(frame(5)));
+        assert!(!remote.push(frame(5)));
+
+        remote.pop();
+        assert!(!remote.push(frame(4)));
+        assert!(!remote.push(frame(5)));
+        assert!(remote.push(frame(6)));
+    }
+
+    #[test]
+    fn drops_frames_past_capacity() {
+        let mut remote = RemoteInput {
+            capacity: 2,
+            ..default()
+        };
+        assert!(remote.push(frame(0)));
+        assert!(remote.push(frame(1)));
+        assert!(!remote.push(frame(2)));
+
+        remote.pop();
+        assert!(remote.push(frame(2)));
+    }
+
+    #[test]
+    fn repeats_last_frame_without_jump_when_missing() {
+        let mut remote = RemoteInput::default();
+        remote.push(frame(0));
+        remote.push(frame(2));
+
+        assert!(remote.pop().unwrap().jump);
+        let repeated = remote.pop().unwrap();
+        assert_eq!(repeated.tick, 1);
+        assert!(!repeated.jump);
+        assert_eq!(repeated.movement, Some(Vec2::Y));
+        assert_eq!(remote.missed_frames(), 1);
+        assert_eq!(remote.pop().unwrap().tick, 2);
+    }
+
+    #[test]
+    fn pops_nothing_before_the_first_frame() {
+        let mut remote = RemoteInput::default();
+        assert_eq!(remote.pop(), None);
+        assert_eq!(remote.next_tick(), None);
+    }
+}
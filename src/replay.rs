@@ -0,0 +1,311 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::time::Duration;
+
+use crate::{CharacterControllerState, prelude::*};
+
+/// Records characters into [`GhostTrack`]s and plays them back on ghosts.
+pub struct AhoyReplayPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            record_ghost_tracks.after(AhoySystems::MoveCharacters),
+        )
+        .add_systems(Update, play_ghost_tracks);
+    }
+}
+
+/// Records the movement of a character into a [`GhostTrack`], one frame per tick, e.g. to race
+/// against it later with a [`GhostPlayback`].
+///
+/// Nothing is recorded until [`Self::start`] is called.
+#[derive(Component, Clone, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct GhostRecorder {
+    /// The track recorded so far.
+    pub track: GhostTrack,
+    recording: bool,
+}
+
+impl GhostRecorder {
+    /// Clears the track and starts recording a new one on the next tick.
+    pub fn start(&mut self) {
+        self.track.frames.clear();
+        self.recording = true;
+    }
+
+    /// Stops recording and returns the recorded track.
+    pub fn stop(&mut self) -> GhostTrack {
+        self.recording = false;
+        core::mem::take(&mut self.track)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+}
+
+/// The movement of a character over time, recorded by a [`GhostRecorder`].
+///
+/// Save it with [`Self::to_bytes`] and load it again with [`Self::from_bytes`].
+#[derive(Clone, Reflect, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GhostTrack {
+    /// The time between two frames.
+    pub timestep: Duration,
+    pub frames: Vec<GhostFrame>,
+}
+
+/// The state of a character on a single tick of a [`GhostTrack`].
+#[derive(Clone, Copy, Reflect, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GhostFrame {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub crouching: bool,
+    pub grounded: bool,
+}
+
+/// The size of a single frame in [`GhostTrack::to_bytes`].
+const FRAME_SIZE: usize = 7 * 4 + 1;
+const CROUCHING: u8 = 1 << 0;
+const GROUNDED: u8 = 1 << 1;
+
+impl GhostTrack {
+    /// How long it takes to play back the whole track.
+    pub fn duration(&self) -> Duration {
+        self.timestep * self.frames.len().saturating_sub(1) as u32
+    }
+
+    /// The frame at `elapsed` time into the track, interpolated between the two closest frames.
+    ///
+    /// Returns `None` if the track is empty. Past the end of the track, this is the last frame.
+    pub fn sample(&self, elapsed: Duration) -> Option<GhostFrame> {
+        let last = self.frames.last()?;
+        if self.timestep.is_zero() {
+            return Some(*last);
+        }
+        let position = elapsed.as_secs_f32() / self.timestep.as_secs_f32();
+        let index = position.floor() as usize;
+        let (Some(from), Some(to)) = (self.frames.get(index), self.frames.get(index + 1)) else {
+            return Some(*last);
+        };
+        let t = position.fract();
+        Some(GhostFrame {
+            translation: from.translation.lerp(to.translation, t),
+            rotation: from.rotation.slerp(to.rotation, t),
+            ..*from
+        })
+    }
+
+    /// Encodes the track into a compact binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + self.frames.len() * FRAME_SIZE);
+        bytes.extend_from_slice(&(self.timestep.as_nanos() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            let floats = frame
+                .translation
+                .to_array()
+                .into_iter()
+                .chain(frame.rotation.to_array());
+            for float in floats {
+                bytes.extend_from_slice(&float.to_le_bytes());
+            }
+            let mut flags = 0;
+            if frame.crouching {
+                flags |= CROUCHING;
+            }
+            if frame.grounded {
+                flags |= GROUNDED;
+            }
+            bytes.push(flags);
+        }
+        bytes
+    }
+
+    /// Decodes a track encoded with [`Self::to_bytes`].
+    ///
+    /// Returns `None` if `bytes` is not a valid track.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (timestep, bytes) = bytes.split_first_chunk::<8>()?;
+        let (len, bytes) = bytes.split_first_chunk::<4>()?;
+        let len = u32::from_le_bytes(*len) as usize;
+        if bytes.len() != len.checked_mul(FRAME_SIZE)? {
+            return None;
+        }
+        let frames = bytes
+            .chunks_exact(FRAME_SIZE)
+            .map(|frame| {
+                let float = |i: usize| {
+                    f32::from_le_bytes([
+                        frame[i * 4],
+                        frame[i * 4 + 1],
+                        frame[i * 4 + 2],
+                        frame[i * 4 + 3],
+                    ])
+                };
+                let flags = frame[FRAME_SIZE - 1];
+                GhostFrame {
+                    translation: vec3(float(0), float(1), float(2)),
+                    rotation: Quat::from_xyzw(float(3), float(4), float(5), float(6)),
+                    crouching: flags & CROUCHING != 0,
+                    grounded: flags & GROUNDED != 0,
+                }
+            })
+            .collect();
+        Some(Self {
+            timestep: Duration::from_nanos(u64::from_le_bytes(*timestep)),
+            frames,
+        })
+    }
+}
+
+/// Plays a [`GhostTrack`] back by moving this entity along it, e.g. to race against a previous run.
+///
+/// The ghost is moved every frame, interpolating between the recorded ticks. It does not collide
+/// with anything, so it is usually just a mesh. Triggers [`GhostPlaybackFinished`] when the end of
+/// the track is reached.
+#[derive(Component, Clone, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct GhostPlayback {
+    pub track: GhostTrack,
+    /// How far into the track the ghost is.
+    pub elapsed: Duration,
+    /// Start over once the end of the track is reached.
+    pub looping: bool,
+    finished: bool,
+}
+
+impl GhostPlayback {
+    pub fn new(track: GhostTrack) -> Self {
+        Self { track, ..default() }
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// Starts the playback over from the beginning.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+    }
+
+    /// The frame the ghost is currently at, e.g. to show it crouching.
+    pub fn current_frame(&self) -> Option<GhostFrame> {
+        self.track.sample(self.elapsed)
+    }
+}
+
+/// Triggered when a [`GhostPlayback`] reached the end of its track.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct GhostPlaybackFinished {
+    /// The ghost.
+    pub entity: Entity,
+}
+
+fn record_ghost_tracks(
+    mut characters: Query<(&mut GhostRecorder, &Transform, &CharacterControllerState)>,
+    time: Res<Time>,
+) {
+    for (mut recorder, transform, state) in &mut characters {
+        if !recorder.recording {
+            continue;
+        }
+        recorder.track.timestep = time.delta();
+        recorder.track.frames.push(GhostFrame {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            crouching: state.crouching,
+            grounded: state.grounded.is_some(),
+        });
+    }
+}
+
+fn play_ghost_tracks(
+    mut ghosts: Query<(Entity, &mut GhostPlayback, &mut Transform)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut playback, mut transform) in &mut ghosts {
+        if playback.finished {
+            continue;
+        }
+        playback.elapsed += time.delta();
+        let duration = playback.track.duration();
+        if playback.elapsed >= duration {
+            commands.trigger(GhostPlaybackFinished { entity });
+            if playback.looping && !duration.is_zero() {
+                playback.elapsed = Duration::from_nanos(
+                    (playback.elapsed.as_nanos() % duration.as_nanos()) as u64,
+                );
+            } else {
+                playback.elapsed = duration;
+                playback.finished = true;
+            }
+        }
+        let Some(frame) = playback.current_frame() else {
+            continue;
+        };
+        transform.translation = frame.translation;
+        transform.rotation = frame.rotation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> GhostTrack {
+        GhostTrack {
+            timestep: Duration::from_secs_f64(1.0 / 64.0),
+            frames: vec![
+                GhostFrame {
+                    translation: vec3(1.0, -2.5, 3.25),
+                    rotation: Quat::from_rotation_y(0.5),
+                    crouching: true,
+                    grounded: false,
+                },
+                GhostFrame {
+                    translation: vec3(-100.0, 0.0, 1e6),
+                    rotation: Quat::IDENTITY,
+                    crouching: false,
+                    grounded: true,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let track = track();
+        let bytes = track.to_bytes();
+        assert_eq!(bytes.len(), 12 + 2 * FRAME_SIZE);
+
+        let decoded = GhostTrack::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.timestep, track.timestep);
+        assert_eq!(decoded.frames, track.frames);
+    }
+
+    #[test]
+    fn empty_track_round_trips() {
+        let decoded = GhostTrack::from_bytes(&GhostTrack::default().to_bytes()).unwrap();
+        assert_eq!(decoded.timestep, Duration::ZERO);
+        assert!(decoded.frames.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_bytes() {
+        let bytes = track().to_bytes();
+        assert!(GhostTrack::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        assert!(GhostTrack::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_none());
+        assert!(GhostTrack::from_bytes(&bytes[..10]).is_none());
+        assert!(GhostTrack::from_bytes(&[]).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use bevy_ecs::error::BevyError;
+
+use crate::{
+    CharacterControllerState, CharacterLook, WishVelocity, input::AccumulatedInput, prelude::*,
+};
+
+/// A copy of everything that determines how a character moves on the next tick.
+///
+/// Capture one per tick to rewind a character later, e.g. for rollback networking or kill-cams.
+/// After [restoring](Self::restore) a snapshot, stepping the character with the same inputs
+/// reproduces the same movement.
+#[derive(Clone, Reflect, Debug)]
+pub struct CharacterSnapshot {
+    pub transform: Transform,
+    pub velocity: LinearVelocity,
+    /// Includes the grounded state, the platform velocities, and all stopwatches.
+    pub state: CharacterControllerState,
+    /// Includes the buffered jump input.
+    pub input: AccumulatedInput,
+    pub water: WaterState,
+    pub wish: WishVelocity,
+    pub look: Option<CharacterLook>,
+}
+
+impl CharacterSnapshot {
+    /// Captures the current movement state of the character `entity`.
+    ///
+    /// Returns `None` if `entity` is not a character.
+    pub fn capture(world: &World, entity: Entity) -> Option<Self> {
+        let entity = world.get_entity(entity).ok()?;
+        Some(Self {
+            transform: *entity.get::<Transform>()?,
+            velocity: *entity.get::<LinearVelocity>()?,
+            state: entity.get::<CharacterControllerState>()?.clone(),
+            input: entity.get::<AccumulatedInput>()?.clone(),
+            water: *entity.get::<WaterState>()?,
+            wish: *entity.get::<WishVelocity>()?,
+            look: entity.get::<CharacterLook>().cloned(),
+        })
+    }
+
+    /// Restores the character `entity` to the state it had when this snapshot was captured.
+    ///
+    /// The physics [`Position`] and [`Rotation`] are restored as well, so that the character can
+    /// be re-simulated right away, e.g. with [`step_character`](crate::step_character).
+    pub fn restore(&self, world: &mut World, entity: Entity) -> Result<(), BevyError> {
+        let mut entity = world.get_entity_mut(entity)?;
+        entity.insert((
+            self.transform,
+            Position(self.transform.translation),
+            Rotation(self.transform.rotation),
+            self.velocity,
+            self.state.clone(),
+            self.input.clone(),
+            self.water,
+            self.wish,
+        ));
+        if let Some(look) = &self.look {
+            entity.insert(look.clone());
+        }
+        Ok(())
+    }
+}
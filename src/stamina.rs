@@ -0,0 +1,145 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use bevy_time::Stopwatch;
+use core::time::Duration;
+
+use crate::{CharacterControllerState, prelude::*};
+
+/// Drains and regenerates the [`Stamina`] of characters.
+pub struct AhoyStaminaPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyStaminaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            update_stamina.after(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// Limits how long a character can sprint and swim, and how often it can [`Vault`].
+///
+/// Sprinting and swimming drain stamina, and every vault costs some. Once it runs out, the
+/// character can't sprint, swim up, or vault until it regenerated [`Self::recover_fraction`] of
+/// its stamina, triggering [`StaminaDepleted`] and [`StaminaRecovered`] along the way.
+#[derive(Component, Clone, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// How much stamina sprinting costs per second.
+    pub sprint_drain: f32,
+    /// How much stamina swimming costs per second.
+    pub swim_drain: f32,
+    /// How much stamina starting a vault costs.
+    pub vault_cost: f32,
+    /// How much stamina is regenerated per second while not draining.
+    pub regen_rate: f32,
+    /// How long the character has to stop draining stamina before it regenerates.
+    pub regen_delay: Duration,
+    /// The fraction of [`Self::max`] a depleted character has to regenerate before it can sprint,
+    /// swim up, or vault again.
+    pub recover_fraction: f32,
+    #[reflect(ignore)]
+    depleted: bool,
+    #[reflect(ignore)]
+    since_drain: Stopwatch,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl Stamina {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            sprint_drain: 20.0,
+            swim_drain: 10.0,
+            vault_cost: 15.0,
+            regen_rate: 25.0,
+            regen_delay: Duration::from_secs(1),
+            recover_fraction: 0.3,
+            depleted: false,
+            since_drain: Stopwatch::new(),
+        }
+    }
+
+    /// Whether the stamina ran out and has not recovered yet.
+    pub fn is_depleted(&self) -> bool {
+        self.depleted
+    }
+
+    /// Spends `amount` stamina at once, e.g. for an action, and delays regenerating it by
+    /// [`Self::regen_delay`].
+    pub fn spend(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+        self.since_drain.reset();
+    }
+
+    /// The current stamina, from `0.0` for empty to `1.0` for full, e.g. for a stamina bar.
+    pub fn fraction(&self) -> f32 {
+        (self.current / self.max.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+/// Triggered when the [`Stamina`] of a character runs out.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct StaminaDepleted {
+    pub entity: Entity,
+}
+
+/// Triggered when a character with depleted [`Stamina`] can sprint, swim up, and vault again.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct StaminaRecovered {
+    pub entity: Entity,
+}
+
+fn update_stamina(
+    mut characters: Query<
+        (
+            Entity,
+            &mut Stamina,
+            &CharacterControllerState,
+            &WaterState,
+            &WishVelocity,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut stamina, state, water, wish) in &mut characters {
+        let swimming = water.level > WaterLevel::Feet && wish.spatial != Vec3::ZERO;
+        let mut drain = 0.0;
+        if state.sprinting {
+            drain += stamina.sprint_drain;
+        }
+        if swimming {
+            drain += stamina.swim_drain;
+        }
+
+        if drain > 0.0 {
+            stamina.current = (stamina.current - drain * time.delta_secs()).max(0.0);
+            stamina.since_drain.reset();
+        } else {
+            stamina.since_drain.tick(time.delta());
+            if stamina.since_drain.elapsed() >= stamina.regen_delay {
+                stamina.current =
+                    (stamina.current + stamina.regen_rate * time.delta_secs()).min(stamina.max);
+            }
+        }
+
+        if !stamina.depleted && stamina.current <= 0.0 {
+            stamina.depleted = true;
+            commands.trigger(StaminaDepleted { entity });
+        } else if stamina.depleted && stamina.current >= stamina.max * stamina.recover_fraction {
+            stamina.depleted = false;
+            commands.trigger(StaminaRecovered { entity });
+        }
+    }
+}
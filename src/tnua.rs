@@ -0,0 +1,79 @@
+use core::time::Duration;
+
+use crate::prelude::*;
+
+/// The common `bevy_tnua` parameters of a character, to migrate it to a [`CharacterController`].
+///
+/// Copy the values from your `TnuaBuiltinWalk` and `TnuaBuiltinJump` and convert this with
+/// [`CharacterController::from`]. The result is a starting point: Ahoy accelerates and brakes
+/// differently than Tnua, so expect to tweak the friction and acceleration afterwards.
+#[derive(Clone, Copy, Reflect, Debug)]
+pub struct TnuaParameters {
+    /// The `float_height` of `TnuaBuiltinWalk`, measured from the rigid body's origin.
+    pub float_height: f32,
+    /// Half the height of the character's collider.
+    pub collider_half_height: f32,
+    /// The length of the `desired_velocity` you pass to `TnuaBuiltinWalk` when walking.
+    pub speed: f32,
+    /// The `acceleration` of `TnuaBuiltinWalk`.
+    pub acceleration: f32,
+    /// The `air_acceleration` of `TnuaBuiltinWalk`.
+    pub air_acceleration: f32,
+    /// The `max_slope` of `TnuaBuiltinWalk`, in radians.
+    pub max_slope: f32,
+    /// The `coyote_time` of `TnuaBuiltinWalk`, in seconds.
+    pub coyote_time: f32,
+    /// The `height` of `TnuaBuiltinJump`.
+    pub jump_height: f32,
+}
+
+impl Default for TnuaParameters {
+    /// The defaults of Tnua for a character with a collider of Ahoy's default height.
+    fn default() -> Self {
+        Self {
+            float_height: 1.0,
+            collider_half_height: 0.9,
+            speed: 10.0,
+            acceleration: 60.0,
+            air_acceleration: 20.0,
+            max_slope: core::f32::consts::FRAC_PI_2,
+            coyote_time: 0.15,
+            jump_height: 4.0,
+        }
+    }
+}
+
+impl From<TnuaParameters> for CharacterController {
+    fn from(tnua: TnuaParameters) -> Self {
+        let speed = tnua.speed.max(f32::EPSILON);
+        let defaults = Self::default();
+        // Tnua floats over small obstacles, Ahoy steps onto them instead
+        let step_size =
+            (tnua.float_height - tnua.collider_half_height).max(defaults.ground.step_size);
+        Self {
+            speed: tnua.speed,
+            // Tnua characters are pulled down by the physics gravity
+            use_physics_gravity: true,
+            ground: GroundConfig {
+                // Ahoy's acceleration is relative to the wish speed
+                acceleration_hz: tnua.acceleration / speed,
+                ..defaults.ground.clone()
+            },
+            air: AirConfig {
+                // Tnua has full air control
+                speed: tnua.speed,
+                acceleration_hz: tnua.air_acceleration / speed,
+                control: AirControl::Direct {
+                    deceleration_hz: tnua.air_acceleration / speed,
+                },
+            },
+            jump: JumpConfig {
+                height: tnua.jump_height,
+                coyote_time: Duration::from_secs_f32(tnua.coyote_time.max(0.0)),
+                ..defaults.jump.clone()
+            },
+            ..defaults
+        }
+        .with_terrain(step_size, tnua.max_slope)
+    }
+}
@@ -0,0 +1,217 @@
+use bevy_ecs::{
+    intern::Interned, lifecycle::HookContext, schedule::ScheduleLabel, world::DeferredWorld,
+};
+use bevy_trenchbroom::prelude::*;
+use tracing::error;
+
+use crate::{CharacterLook, prelude::*};
+
+/// Drives the TrenchBroom entity classes of `bevy_ahoy`.
+///
+/// The classes are registered with `bevy_trenchbroom` automatically. Add them to your game
+/// config to place player starts, water, push and teleport triggers, and moving platforms in
+/// your maps.
+pub struct AhoyTrenchBroomPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyTrenchBroomPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            build_train_paths.before(AhoySystems::MoveCharacters),
+        )
+        .add_observer(push_characters)
+        .add_observer(teleport_characters);
+    }
+}
+
+/// Marks where players spawn.
+///
+/// Setting up a player is specific to every game, so observe `On<Add, InfoPlayerStart>` to spawn
+/// yours at its [`Transform`].
+#[point_class(base(Transform, Visibility))]
+#[derive(Default)]
+pub struct InfoPlayerStart;
+
+/// A brush that characters can swim in. Inserts a [`Water`].
+#[solid_class(base(Transform, Visibility))]
+#[component(on_add = Self::on_add)]
+pub struct FuncWater {
+    /// See [`Water::speed`].
+    pub speed: f32,
+    /// See [`Water::buoyancy`].
+    pub buoyancy: f32,
+}
+
+impl Default for FuncWater {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            buoyancy: 0.0,
+        }
+    }
+}
+
+impl FuncWater {
+    fn on_add(mut world: DeferredWorld, ctx: HookContext) {
+        if world.is_scene_world() {
+            return;
+        }
+        let Some(&FuncWater { speed, buoyancy }) = world.get::<FuncWater>(ctx.entity) else {
+            return;
+        };
+        world.commands().entity(ctx.entity).insert(Water {
+            speed,
+            buoyancy,
+            ..default()
+        });
+    }
+}
+
+/// A trigger brush that boosts the speed of characters entering it, keeping their direction.
+#[solid_class(base(Transform, Visibility))]
+#[derive(Default)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct TriggerPush {
+    /// The speed added to the character.
+    pub speed: f32,
+}
+
+fn push_characters(
+    start: On<CollisionStart>,
+    pushes: Query<&TriggerPush>,
+    mut characters: Query<&mut LinearVelocity, With<CharacterController>>,
+) {
+    let Ok(push) = pushes.get(start.collider1) else {
+        return;
+    };
+    let Ok(mut velocity) = characters.get_mut(start.body2.unwrap_or(start.collider2)) else {
+        return;
+    };
+    let Ok((direction, speed)) = Dir3::new_and_length(velocity.0) else {
+        return;
+    };
+    velocity.0 = direction * (speed + push.speed);
+}
+
+/// A trigger brush that teleports characters entering it to the [`InfoTeleportDestination`]
+/// named by [`Self::target`].
+#[solid_class(base(Transform, Visibility))]
+#[derive(Default)]
+#[require(Sensor, CollisionEventsEnabled)]
+pub struct TriggerTeleport {
+    pub target: String,
+}
+
+/// Where a [`TriggerTeleport`] sends characters. They are placed at its position, look in its
+/// direction, and lose all their velocity.
+#[point_class(base(Transform, Visibility))]
+#[derive(Default)]
+pub struct InfoTeleportDestination {
+    #[class(must_set)]
+    pub targetname: String,
+}
+
+fn teleport_characters(
+    start: On<CollisionStart>,
+    teleports: Query<&TriggerTeleport>,
+    destinations: Query<(&Transform, &InfoTeleportDestination)>,
+    mut characters: Query<
+        (
+            &mut Transform,
+            &mut Position,
+            &mut LinearVelocity,
+            Option<&mut CharacterLook>,
+        ),
+        (With<CharacterController>, Without<InfoTeleportDestination>),
+    >,
+) {
+    let Ok(teleport) = teleports.get(start.collider1) else {
+        return;
+    };
+    let Ok((mut transform, mut position, mut velocity, look)) =
+        characters.get_mut(start.body2.unwrap_or(start.collider2))
+    else {
+        return;
+    };
+    let Some((destination, _)) = destinations
+        .iter()
+        .find(|(_, destination)| destination.targetname == teleport.target)
+    else {
+        error!(
+            "InfoTeleportDestination not found for target: {}",
+            teleport.target
+        );
+        return;
+    };
+    transform.translation = destination.translation;
+    position.0 = destination.translation;
+    velocity.0 = Vec3::ZERO;
+    if let Some(mut look) = look {
+        *look = CharacterLook::from_quat(destination.rotation);
+    }
+}
+
+/// A moving platform that travels along a chain of [`PathCorner`]s, starting with the one named
+/// by [`Self::target`]. Characters standing on it move along.
+///
+/// The chain is turned into a [`PathMover`] once the map is loaded. If the chain leads back to a
+/// corner it already visited, the train loops, otherwise it stops at the last corner. Moving it
+/// needs [`AhoyPathMoverPlugin`](crate::AhoyPathMoverPlugin) from
+/// [`AhoyExtraPlugins`](crate::AhoyExtraPlugins).
+#[solid_class(base(Transform, Visibility))]
+#[derive(Default)]
+#[require(RigidBody::Kinematic, TransformInterpolation, GlobalTransform)]
+pub struct FuncTrain {
+    pub target: String,
+    pub speed: f32,
+    /// The angular velocity of the train.
+    pub rotation: Vec3,
+}
+
+/// A waypoint of a [`FuncTrain`]. Once it is reached, the train continues to [`Self::target`].
+#[point_class(base(Transform, Visibility))]
+#[derive(Default)]
+#[require(GlobalTransform)]
+pub struct PathCorner {
+    #[class(must_set)]
+    pub targetname: String,
+    pub target: String,
+}
+
+fn build_train_paths(
+    trains: Query<(Entity, &FuncTrain), Without<PathMover>>,
+    corners: Query<(&GlobalTransform, &PathCorner)>,
+    mut commands: Commands,
+) {
+    for (entity, train) in &trains {
+        let mut targets = Vec::new();
+        let mut waypoints = Vec::new();
+        let mut mode = PathMode::Once;
+        let mut target = &train.target;
+        while !target.is_empty() {
+            if targets.contains(&target) {
+                mode = PathMode::Loop;
+                break;
+            }
+            let Some((corner_transform, corner)) = corners
+                .iter()
+                .find(|(_, corner)| &corner.targetname == target)
+            else {
+                error!("PathCorner not found for target: {target}");
+                break;
+            };
+            targets.push(target);
+            waypoints.push(PathWaypoint::new(
+                corner_transform.translation(),
+                train.speed,
+            ));
+            target = &corner.target;
+        }
+        commands.entity(entity).insert(PathMover {
+            angular_velocity: train.rotation,
+            ..PathMover::new(waypoints, mode)
+        });
+    }
+}
@@ -0,0 +1,245 @@
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::time::Duration;
+
+use crate::{
+    CharacterControllerDerivedProps, CharacterControllerState,
+    kcc::{CharacterMotion, on_collider_or_body},
+    prelude::*,
+};
+
+/// Lets characters with [`Vault`] vault over low obstacles.
+pub struct AhoyVaultPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl Plugin for AhoyVaultPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            (advance_vaults, start_vaults)
+                .chain()
+                .before(AhoySystems::MoveCharacters),
+        );
+    }
+}
+
+/// Lets a character vault over waist-high obstacles with empty space behind them, e.g. railings
+/// or low walls, by running into them. Instead of climbing on top of the obstacle, the character
+/// quickly moves up, across, and down again along a path that is checked to be free.
+///
+/// While vaulting, the character has a [`VaultProgress`] and is not moved by the KCC.
+#[derive(Component, Clone, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Vault {
+    /// How far in front of the character obstacles are detected.
+    pub detection_distance: f32,
+    /// Obstacles lower than this, measured from the feet, are not vaulted over. Keep this above
+    /// [`GroundConfig::step_size`] so that stairs are still stepped up.
+    pub min_height: f32,
+    /// Obstacles higher than this, measured from the feet, are not vaulted over.
+    pub max_height: f32,
+    /// Obstacles thicker than this are not vaulted over, e.g. because they are a ledge to climb
+    /// onto instead.
+    pub max_depth: f32,
+    /// How far above the top of the obstacle the feet pass.
+    pub clearance: f32,
+    pub duration: Duration,
+    /// Only vault while sprinting. Otherwise, running into an obstacle is enough.
+    pub require_sprint: bool,
+}
+
+impl Default for Vault {
+    fn default() -> Self {
+        Self {
+            detection_distance: 0.5,
+            min_height: 0.75,
+            max_height: 1.2,
+            max_depth: 0.8,
+            clearance: 0.15,
+            duration: Duration::from_millis(400),
+            require_sprint: true,
+        }
+    }
+}
+
+/// The state of a vault in progress. Removed once the character landed behind the obstacle.
+#[derive(Component, Clone, Reflect, Debug)]
+#[reflect(Component)]
+pub struct VaultProgress {
+    /// The obstacle being vaulted over.
+    pub obstacle: Entity,
+    /// Where the vault started, expressed in world space.
+    pub start: Vec3,
+    /// Where the character rises to above [`Self::start`], high enough to clear the obstacle,
+    /// expressed in world space.
+    pub raised: Vec3,
+    /// Where the character is moved to past the obstacle before coming down, expressed in world
+    /// space.
+    pub over: Vec3,
+    /// Where the vault ends, expressed in world space.
+    pub end: Vec3,
+    pub elapsed: Duration,
+    pub duration: Duration,
+    /// The velocity the character keeps after the vault.
+    pub exit_velocity: Vec3,
+}
+
+impl VaultProgress {
+    /// How far along the vault is, from `0.0` to `1.0`, e.g. for driving an animation.
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    /// The position along the path at [`Self::fraction`].
+    pub fn position(&self) -> Vec3 {
+        // Follow exactly the path that was checked to be free when the vault started, at a
+        // constant speed
+        let points = [self.start, self.raised, self.over, self.end];
+        let lengths = [0, 1, 2].map(|i| points[i].distance(points[i + 1]));
+        let mut distance = self.fraction() * lengths.iter().sum::<f32>();
+        for (i, length) in lengths.into_iter().enumerate() {
+            if distance <= length && length > 0.0 {
+                return points[i].lerp(points[i + 1], distance / length);
+            }
+            distance -= length;
+        }
+        self.end
+    }
+}
+
+/// Triggered when a character starts vaulting over an obstacle.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct VaultStarted {
+    /// The character that is vaulting.
+    pub entity: Entity,
+    /// The collider being vaulted over.
+    pub obstacle: Entity,
+}
+
+fn start_vaults(
+    mut characters: Query<
+        (
+            Entity,
+            &Vault,
+            &CharacterController,
+            &CharacterControllerState,
+            &CharacterControllerDerivedProps,
+            &Transform,
+            &LinearVelocity,
+            &WishVelocity,
+            Option<&mut Stamina>,
+        ),
+        (Without<VaultProgress>, Without<CharacterControllerDisabled>),
+    >,
+    no_climb: Query<(), With<NoClimb>>,
+    colliders: Query<&ColliderOf>,
+    motion: CharacterMotion,
+    spatial_query: SpatialQuery,
+    mut commands: Commands,
+) {
+    for (entity, vault, cfg, state, derived, transform, velocity, wish, stamina) in &mut characters
+    {
+        if state.grounded.is_none() || (vault.require_sprint && !state.sprinting) {
+            continue;
+        }
+        if stamina.as_deref().is_some_and(Stamina::is_depleted) {
+            continue;
+        }
+        let Ok(forward) = Dir3::new(wish.planar) else {
+            continue;
+        };
+
+        // Find the front of the obstacle
+        let Ok(Some(front)) = motion.cast_move(entity, forward * vault.detection_distance) else {
+            continue;
+        };
+        let Ok(true) = motion.is_wall(entity, front.entity, front.normal1) else {
+            continue;
+        };
+        if on_collider_or_body(front.entity, &no_climb, &colliders).is_some() {
+            continue;
+        }
+
+        // Find the top of the obstacle
+        let feet = transform.translation.y + derived.pos_to_feet_dist(state);
+        let radius = derived.radius(state);
+        // Start the ray slightly behind the front face and above the highest vaultable obstacle
+        let ray_height = vault.max_height + 0.05;
+        let origin = (front.point1 + forward * 0.05).with_y(feet + ray_height);
+        let Some(top) = spatial_query.cast_ray(origin, Dir3::NEG_Y, ray_height, true, &cfg.filter)
+        else {
+            continue;
+        };
+        let height = ray_height - top.distance;
+        if height < vault.min_height || height > vault.max_height {
+            continue;
+        }
+
+        // Make room above the obstacle
+        let rise = height + vault.clearance;
+        let Ok(None) = motion.cast_move(entity, Vec3::Y * rise) else {
+            continue;
+        };
+        let raised = transform.translation + Vec3::Y * rise;
+
+        // Clear the obstacle
+        let across = front.distance + vault.max_depth + radius * 2.0;
+        let Ok(None) = motion.cast_move_from(entity, raised, forward * across) else {
+            continue;
+        };
+        let over = raised + forward * across;
+
+        // Land behind it, but not on top of a deeper obstacle
+        let Ok(landing) = motion.cast_move_from(entity, over, Vec3::NEG_Y * rise) else {
+            continue;
+        };
+        let end = match landing {
+            Some(landing) if landing.distance < rise - cfg.ground.step_size => continue,
+            Some(landing) => over - Vec3::Y * landing.distance,
+            None => over - Vec3::Y * rise,
+        };
+
+        if let Some(mut stamina) = stamina {
+            let cost = stamina.vault_cost;
+            stamina.spend(cost);
+        }
+        let horizontal_speed = velocity.xz().length();
+        commands.entity(entity).insert(VaultProgress {
+            obstacle: front.entity,
+            start: transform.translation,
+            raised,
+            over,
+            end,
+            elapsed: Duration::ZERO,
+            duration: vault.duration,
+            exit_velocity: forward * horizontal_speed,
+        });
+        commands.trigger(VaultStarted {
+            entity,
+            obstacle: front.entity,
+        });
+    }
+}
+
+fn advance_vaults(
+    mut characters: Query<(
+        Entity,
+        &mut VaultProgress,
+        &mut Transform,
+        &mut LinearVelocity,
+    )>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut progress, mut transform, mut velocity) in &mut characters {
+        progress.elapsed += time.delta();
+        transform.translation = progress.position();
+        velocity.0 = progress.exit_velocity;
+        if progress.fraction() >= 1.0 {
+            commands.entity(entity).remove::<VaultProgress>();
+        }
+    }
+}
@@ -1,24 +1,63 @@
-use crate::{CharacterControllerState, prelude::*};
+use bevy_ecs::{intern::Interned, schedule::ScheduleLabel};
+use core::fmt;
+use std::sync::Arc;
 
-pub struct AhoyWaterPlugin;
+use crate::{CharacterControllerDerivedProps, CharacterControllerState, prelude::*};
+
+pub struct AhoyWaterPlugin {
+    pub schedule: Interned<dyn ScheduleLabel>,
+}
 
 impl Plugin for AhoyWaterPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            FixedUpdate,
+            self.schedule,
             update_water.before(AhoySystems::MoveCharacters),
         );
     }
 }
 
-#[derive(Component, Default, Copy, Reflect, Clone, Debug)]
+#[derive(Component, Copy, Reflect, Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct WaterState {
     pub level: WaterLevel,
+    /// The water the character is submerged in the deepest, if any.
+    /// Not serialized, as it is detected again on the next tick.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub water: Option<Entity>,
+    /// The height of the surface of [`Self::water`], expressed in world space.
+    /// Not serialized, as it is detected again on the next tick.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub surface: Option<f32>,
+    /// The [`Water::speed`] of the slowest water the character touches, or `1.0` outside of
+    /// water. Scales the wished speed of the character while swimming.
     pub speed: f32,
+    /// The velocity of the current the character is in. Carries the character along while
+    /// swimming.
+    pub current: Vec3,
+    /// The water whose current is strong enough to override the character's input, if any.
+    /// Not serialized, as it is detected again on the next tick.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub caught_in_current: Option<Entity>,
+}
+
+impl Default for WaterState {
+    fn default() -> Self {
+        Self {
+            level: WaterLevel::None,
+            water: None,
+            surface: None,
+            speed: 1.0,
+            current: Vec3::ZERO,
+            caught_in_current: None,
+        }
+    }
 }
 
 #[derive(Default, Copy, Reflect, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum WaterLevel {
     #[default]
     None,
@@ -27,45 +66,262 @@ pub enum WaterLevel {
     Head,
 }
 
-#[derive(Reflect, Component, Default)]
+#[derive(Reflect, Component)]
 #[require(Sensor, Transform, GlobalTransform)]
 #[reflect(Component)]
 pub struct Water {
+    /// Scales how fast characters swim in this water, e.g. `0.5` for thick mud. Wading characters
+    /// walk at their usual speed.
     pub speed: f32,
+    /// The vertical speed characters drift at while idling in this water. Positive values make
+    /// them float up like in dense brine, negative values make them sink like in a heavy liquid.
+    /// Characters with a [`WaterConfig::float_height`] float higher or lower instead.
+    pub buoyancy: f32,
+    /// The velocity of the water's flow, expressed in world space, e.g. for rivers. Swimming
+    /// characters are carried along by it on top of their own movement.
+    pub current: Vec3,
+    /// The current speed at or above which the flow fully overrides the input of characters
+    /// swimming in it, like rapids or waterfalls. `None` means the current can always be
+    /// swum against.
+    pub overpowering_current: Option<f32>,
+}
+
+impl Default for Water {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            buoyancy: 0.0,
+            current: Vec3::ZERO,
+            overpowering_current: None,
+        }
+    }
+}
+
+/// Animates the surface of a [`Water`], e.g. to match waves of a shader or a Gerstner function.
+///
+/// The function returns the height of the surface in world space at a horizontal position and
+/// elapsed time in seconds, and is sampled for every character in the water. Above that height,
+/// the water's sensor counts as air, so make the sensor reach up to the highest wave. The sensor
+/// still decides where the water is horizontally.
+#[derive(Component, Clone)]
+pub struct WaterSurface(Arc<dyn Fn(Vec2, f32) -> f32 + Send + Sync>);
+
+impl WaterSurface {
+    pub fn new(height: impl Fn(Vec2, f32) -> f32 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(height))
+    }
+
+    /// The height of the surface at `position` after `elapsed_secs`, expressed in world space.
+    pub fn height(&self, position: Vec2, elapsed_secs: f32) -> f32 {
+        (self.0)(position, elapsed_secs)
+    }
+}
+
+impl fmt::Debug for WaterSurface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WaterSurface").finish_non_exhaustive()
+    }
+}
+
+/// Optional swimming mode for diving-focused games or swimming power-ups.
+///
+/// While swimming, a character with this component is constantly propelled toward its view
+/// direction and can strafe, instead of swimming freely. It is not affected by the water's
+/// buoyancy.
+#[derive(Component, Clone, Copy, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Scuba {
+    /// The speed the character is propelled with toward its view direction.
+    pub thrust_speed: f32,
+    /// The speed the character strafes with.
+    pub strafe_speed: f32,
+    /// How quickly the character reaches its desired velocity.
+    pub acceleration_hz: f32,
+    /// The maximum speed reachable by thrusting and strafing combined.
+    pub max_speed: f32,
+}
+
+impl Default for Scuba {
+    fn default() -> Self {
+        Self {
+            thrust_speed: 8.0,
+            strafe_speed: 4.0,
+            acceleration_hz: 4.0,
+            max_speed: 10.0,
+        }
+    }
+}
+
+/// Triggered when the [`WaterLevel`] of a character changes, e.g. to play splashes when entering
+/// water or to muffle audio once the head is submerged.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct WaterLevelChanged {
+    /// The character whose water level changed.
+    pub entity: Entity,
+    /// The water level before the change.
+    pub old: WaterLevel,
+    /// The water level after the change.
+    pub new: WaterLevel,
+    /// The water responsible for the change. When leaving water entirely, this is the water
+    /// that was left.
+    pub water: Option<Entity>,
+    /// How fast the character moved down into the water, in meters per second, e.g. for scaling
+    /// splashes. Negative while rising out of it.
+    pub submersion_speed: f32,
+}
+
+impl WaterLevelChanged {
+    /// Whether the character went deeper into the water.
+    pub fn is_submerging(&self) -> bool {
+        self.new > self.old
+    }
+
+    /// Whether the character entered water after being out of it.
+    pub fn is_entering(&self) -> bool {
+        self.old == WaterLevel::None
+    }
+
+    /// Whether the character left water entirely.
+    pub fn is_leaving(&self) -> bool {
+        self.new == WaterLevel::None
+    }
+}
+
+/// Triggered when a character is caught by a current it cannot swim against.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct CaughtInCurrent {
+    /// The character that was caught.
+    pub entity: Entity,
+    /// The water whose current caught the character.
+    pub water: Entity,
+}
+
+/// Triggered when a character is released from a current it could not swim against.
+#[derive(EntityEvent, Clone, Copy, Debug)]
+pub struct ReleasedFromCurrent {
+    /// The character that was released.
+    pub entity: Entity,
+    /// The water whose current released the character.
+    pub water: Entity,
 }
 
 fn update_water(
-    mut kccs: Query<(
+    mut kccs: Query<
+        (
+            Entity,
+            &Transform,
+            &CharacterController,
+            &CharacterControllerState,
+            &CharacterControllerDerivedProps,
+            &LinearVelocity,
+            &mut WaterState,
+        ),
+        Without<CharacterControllerDisabled>,
+    >,
+    waters: Query<(
+        Entity,
+        &Collider,
         &Position,
-        &CharacterController,
-        &CharacterControllerState,
-        &mut WaterState,
-        &CollidingEntities,
+        &Rotation,
+        &Water,
+        Option<&WaterSurface>,
     )>,
-    waters: Query<(&Collider, &Position, &Rotation, &Water)>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+    mut commands: Commands,
 ) {
-    for (kcc_center, cfg, state, mut water_state, colliding_entities) in &mut kccs {
+    let mut touched_waters = Vec::new();
+    for (entity, transform, cfg, state, derived, velocity, mut water_state) in &mut kccs {
+        let old_state = *water_state;
         water_state.level = WaterLevel::None;
-        water_state.speed = f32::MAX;
-        let kcc_center = kcc_center.0;
-        let eye_pos = kcc_center
-            + Vec3::Y
-                * if state.crouching {
-                    cfg.crouch_view_height
-                } else {
-                    cfg.standing_view_height
-                };
-        for (collider, position, rotation, water) in waters.iter_many(colliding_entities.iter()) {
-            let level = if collider.contains_point(*position, *rotation, eye_pos) {
+        water_state.water = None;
+        water_state.surface = None;
+        water_state.current = Vec3::ZERO;
+        water_state.caught_in_current = None;
+        // Query the water volumes ourselves instead of relying on `CollidingEntities`, which is
+        // only updated by the physics step and thus lags behind the character's movement.
+        touched_waters.clear();
+        spatial_query.shape_intersections_callback(
+            derived.collider(state),
+            transform.translation,
+            transform.rotation,
+            &cfg.filter,
+            |hit| {
+                if waters.contains(hit) {
+                    touched_waters.push(hit);
+                }
+                true
+            },
+        );
+        // The collider is not necessarily centered on the character's origin
+        let aabb = derived.collider(state).aabb(default(), Rotation::default());
+        let kcc_center = transform.translation + Vec3::Y * (aabb.min.y + aabb.max.y) / 2.0;
+        let eye_pos = transform.translation + Vec3::Y * (aabb.min.y + cfg.view_height(state));
+        let mut strongest_current = 0.0;
+        let mut slowest = None;
+        let feet = transform.translation.y + aabb.min.y;
+        for (water_entity, collider, position, rotation, water, surface) in
+            waters.iter_many(&touched_waters)
+        {
+            let surface = match surface {
+                Some(surface) => surface.height(transform.translation.xz(), time.elapsed_secs()),
+                None => collider.aabb(*position, *rotation).max.y,
+            };
+            if feet >= surface {
+                continue;
+            }
+            let submerged = |point: Vec3| {
+                point.y < surface && collider.contains_point(*position, *rotation, point)
+            };
+            let level = if submerged(eye_pos) {
                 WaterLevel::Head
-            } else if collider.contains_point(*position, *rotation, kcc_center) {
+            } else if submerged(kcc_center) {
                 WaterLevel::Waist
             } else {
                 WaterLevel::Feet
             };
 
-            water_state.level = level.max(water_state.level);
-            water_state.speed = water_state.speed.min(water.speed);
+            if level > water_state.level || water_state.water.is_none() {
+                water_state.level = level;
+                water_state.water = Some(water_entity);
+                water_state.surface = Some(surface);
+            }
+            slowest = Some(slowest.map_or(water.speed, |slowest: f32| slowest.min(water.speed)));
+
+            let current_speed = water.current.length();
+            if current_speed < strongest_current {
+                continue;
+            }
+            strongest_current = current_speed;
+            water_state.current = water.current;
+            // Wading through rapids is fine, only swimming in them gets you swept away
+            water_state.caught_in_current = (level > WaterLevel::Feet
+                && water
+                    .overpowering_current
+                    .is_some_and(|overpowering| current_speed >= overpowering))
+            .then_some(water_entity);
+        }
+
+        water_state.speed = slowest.unwrap_or(1.0);
+
+        if water_state.level != old_state.level {
+            commands.trigger(WaterLevelChanged {
+                entity,
+                old: old_state.level,
+                new: water_state.level,
+                water: water_state.water.or(old_state.water),
+                submersion_speed: -velocity.y,
+            });
+        }
+
+        if water_state.caught_in_current == old_state.caught_in_current {
+            continue;
+        }
+        if let Some(water) = old_state.caught_in_current {
+            commands.trigger(ReleasedFromCurrent { entity, water });
+        }
+        if let Some(water) = water_state.caught_in_current {
+            commands.trigger(CaughtInCurrent { entity, water });
         }
     }
 }